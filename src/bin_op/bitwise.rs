@@ -1,20 +1,19 @@
-use anyhow::Result;
-use serde_json::Value;
+use crate::{
+    util::{value_to_int32, value_to_uint32},
+    value::JsValue,
+};
 
-use crate::util::{number_from_f64, value_to_number};
-
-pub fn bitwise_operation<F>(left: Value, right: Value, operator: F) -> Result<Value>
+pub fn bitwise_operation<F>(left: JsValue, right: JsValue, operator: F) -> JsValue
 where
     F: Fn(i32, i32) -> i32,
 {
-    let left = value_to_number(left)? as i32;
-    let right = value_to_number(right)? as i32;
-    let result = operator(left, right) as f64;
-    Ok(Value::Number(number_from_f64(result)?))
+    let left = value_to_int32(&left);
+    let right = value_to_int32(&right);
+    JsValue::Number(operator(left, right) as f64)
 }
 
-pub fn unsigned_right_shift(left: Value, right: Value) -> Result<Value> {
-    let left = (value_to_number(left)? as i32) as u32;
-    let right = value_to_number(right)? as u32 & 0x1F;
-    Ok(Value::Number(number_from_f64((left >> right) as f64)?))
+pub fn unsigned_right_shift(left: JsValue, right: JsValue) -> JsValue {
+    let left = value_to_uint32(&left);
+    let right = value_to_uint32(&right) & 0x1F;
+    JsValue::Number((left >> right) as f64)
 }
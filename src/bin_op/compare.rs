@@ -1,38 +1,21 @@
-use serde_json::Value;
+use std::cmp::Ordering;
 
-use crate::util::{vec_to_js_string, OBJ_STR};
+use crate::{
+    util::{value_to_number, value_to_primitive},
+    value::JsValue,
+};
 
-use super::util::{number_string, string_number};
-
-pub fn compare<F>(left: &Value, right: &Value, cmp: F) -> bool
-where
-    F: Fn(&str, &str) -> bool + Copy,
-{
-    match (left, right) {
-        (Value::Number(l), Value::Number(r)) => cmp(
-            &l.as_f64().unwrap_or_default().to_string(),
-            &r.as_f64().unwrap_or_default().to_string(),
-        ),
-        (Value::String(l), Value::String(r)) => cmp(l, r),
-        (Value::Number(l), Value::String(r)) => {
-            number_string(l, r, |l, r| cmp(&l.to_string(), &r.to_string()))
-        }
-        (Value::String(l), Value::Number(r)) => {
-            string_number(l, r, |l, r| cmp(&l.to_string(), &r.to_string()))
-        }
-        (Value::Array(l), Value::Array(r)) => {
-            let l = vec_to_js_string(l);
-            let r = vec_to_js_string(r);
-            cmp(&l, &r)
-        }
-        (Value::Array(l), Value::Object(_)) => {
-            let l = vec_to_js_string(l);
-            cmp(&l, OBJ_STR)
-        }
-        (Value::Object(_), Value::Array(r)) => {
-            let r = vec_to_js_string(r);
-            cmp(OBJ_STR, &r)
-        }
-        _ => false,
+/// ECMAScript Abstract Relational Comparison.
+///
+/// Both operands are converted with `ToPrimitive`. Two strings are ordered by
+/// their UTF-16 code units; anything else is compared numerically. Returns
+/// `None` when either side is `NaN`, in which case `<`, `<=`, `>` and `>=` are
+/// all `false`.
+pub fn compare(left: &JsValue, right: &JsValue) -> Option<Ordering> {
+    let left = value_to_primitive(left);
+    let right = value_to_primitive(right);
+    if let (JsValue::String(l), JsValue::String(r)) = (&left, &right) {
+        return Some(l.encode_utf16().cmp(r.encode_utf16()));
     }
+    value_to_number(&left).partial_cmp(&value_to_number(&right))
 }
@@ -1,20 +1,33 @@
-use serde_json::Value;
+use std::rc::Rc;
 
-pub fn equality(left: &Value, right: &Value, strict: bool) -> bool {
+use crate::{
+    util::{value_to_number, value_to_primitive},
+    value::JsValue,
+};
+
+/// ECMAScript `IsStrictlyEqual` (`strict`) or `IsLooselyEqual`.
+///
+/// Arrays and objects are equal only to themselves. Every reference to the
+/// same context variable yields the same value, while literals such as `[1]`
+/// create a new one each time they are evaluated.
+pub fn equality(left: &JsValue, right: &JsValue, strict: bool) -> bool {
     match (left, right) {
-        (Value::Null, Value::Null) => true,
-        (Value::Number(l), Value::Number(r)) => l.as_f64() == r.as_f64(),
-        (Value::String(l), Value::String(r)) => l == r,
-        (Value::Bool(l), Value::Bool(r)) => l == r,
-        (Value::Number(l), Value::String(r)) | (Value::String(r), Value::Number(l)) => {
-            if strict {
-                return false;
-            }
-            match (l.as_f64(), r.parse::<f64>()) {
-                (Some(l), Ok(r)) => l == r,
-                _ => false,
-            }
-        }
-        _ => false,
+        (JsValue::Undefined, JsValue::Undefined) | (JsValue::Null, JsValue::Null) => true,
+        (JsValue::Bool(l), JsValue::Bool(r)) => l == r,
+        (JsValue::Number(l), JsValue::Number(r)) => l == r,
+        (JsValue::String(l), JsValue::String(r)) => l == r,
+        (JsValue::Array(l), JsValue::Array(r)) => Rc::ptr_eq(l, r),
+        (JsValue::Object(l), JsValue::Object(r)) => Rc::ptr_eq(l, r),
+        (JsValue::Function(l), JsValue::Function(r)) => Rc::ptr_eq(l, r),
+        (JsValue::RegExp(l), JsValue::RegExp(r)) => Rc::ptr_eq(l, r),
+        _ if strict => false,
+        (JsValue::Undefined | JsValue::Null, JsValue::Undefined | JsValue::Null) => true,
+        (JsValue::Undefined | JsValue::Null, _) | (_, JsValue::Undefined | JsValue::Null) => false,
+        _ if left.is_object() && right.is_object() => false,
+        _ if left.is_object() => equality(&value_to_primitive(left), right, false),
+        _ if right.is_object() => equality(left, &value_to_primitive(right), false),
+        // The remaining pairs mix booleans, numbers and strings, which are all
+        // compared as numbers.
+        _ => value_to_number(left) == value_to_number(right),
     }
 }
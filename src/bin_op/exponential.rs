@@ -1,10 +1,11 @@
-use anyhow::Result;
-use serde_json::Value;
+use crate::{util::value_to_number, value::JsValue};
 
-use crate::util::{number_from_f64, value_to_number};
-
-pub fn exponential(left: Value, right: Value) -> Result<Value> {
-    let lnum = value_to_number(left)?;
-    let rnum = value_to_number(right)?;
-    Ok(Value::Number(number_from_f64(lnum.powf(rnum))?))
+pub fn exponential(left: JsValue, right: JsValue) -> JsValue {
+    let lnum = value_to_number(&left);
+    let rnum = value_to_number(&right);
+    // Unlike `powf`, JavaScript gives NaN for `1 ** NaN` and `(±1) ** ±Infinity`.
+    if rnum.is_nan() || (lnum.abs() == 1.0 && rnum.is_infinite()) {
+        return JsValue::Number(f64::NAN);
+    }
+    JsValue::Number(lnum.powf(rnum))
 }
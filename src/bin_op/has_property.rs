@@ -0,0 +1,31 @@
+use crate::{
+    error::{EvaluatorError, Result},
+    util::value_to_string,
+    value::JsValue,
+};
+
+/// The `in` operator: whether `object` has the property named `key`.
+///
+/// Arrays have their indices and `length`. Unlike member access, negative
+/// indices are not properties. Anything but an object is a `TypeError`, as in
+/// JavaScript.
+pub fn has_property(key: &JsValue, object: &JsValue) -> Result<bool> {
+    let key = value_to_string(key);
+    match object {
+        JsValue::Object(map) => Ok(map.borrow().contains_key(&key)),
+        JsValue::Array(arr) => {
+            let arr = arr.borrow();
+            let is_index = key
+                .parse::<usize>()
+                .is_ok_and(|index| index < arr.len() && index.to_string() == key);
+            Ok(is_index || key == "length" || arr.properties.contains_key(&key))
+        }
+        JsValue::RegExp(re) => Ok(re.property(&key).is_some()),
+        JsValue::Function(_) => Ok(false),
+        _ => Err(EvaluatorError::type_error(format!(
+            "Cannot use 'in' operator to search for '{}' in {}",
+            key,
+            value_to_string(object)
+        ))),
+    }
+}
@@ -0,0 +1,18 @@
+use crate::value::JsValue;
+
+/// The `instanceof` operator for the built-in constructors `Array`, `Object`,
+/// `RegExp` and `Function`, and for `String`, `Number`, `Boolean` and `Date`,
+/// which nothing is an instance of since wrapper objects and dates can't be
+/// created. Primitives are never instances, as in JavaScript. Returns `None`
+/// for any other constructor name.
+pub fn instance_of(value: &JsValue, constructor: &str) -> Option<bool> {
+    let result = match constructor {
+        "Array" => matches!(value, JsValue::Array(_)),
+        "Object" => value.is_object(),
+        "RegExp" => matches!(value, JsValue::RegExp(_)),
+        "Function" => matches!(value, JsValue::Function(_)),
+        "String" | "Number" | "Boolean" | "Date" => false,
+        _ => return None,
+    };
+    Some(result)
+}
@@ -4,10 +4,11 @@ mod compare;
 mod division;
 mod equality;
 mod exponential;
+mod has_property;
+mod instance_of;
 mod multiplication;
 mod remainder;
 mod subtraction;
-mod util;
 
 pub use self::addition::*;
 pub use self::bitwise::*;
@@ -15,6 +16,8 @@ pub use self::compare::*;
 pub use self::division::*;
 pub use self::equality::*;
 pub use self::exponential::*;
+pub use self::has_property::*;
+pub use self::instance_of::*;
 pub use self::multiplication::*;
 pub use self::remainder::*;
 pub use self::subtraction::*;
@@ -1,10 +1,7 @@
-use anyhow::Result;
-use serde_json::Value;
+use crate::{util::value_to_number, value::JsValue};
 
-use crate::util::{number_from_f64, value_to_number};
-
-pub fn multiplication(left: Value, right: Value) -> Result<Value> {
-    let lnum = value_to_number(left)?;
-    let rnum = value_to_number(right)?;
-    Ok(Value::Number(number_from_f64(lnum * rnum)?))
+pub fn multiplication(left: JsValue, right: JsValue) -> JsValue {
+    let lnum = value_to_number(&left);
+    let rnum = value_to_number(&right);
+    JsValue::Number(lnum * rnum)
 }
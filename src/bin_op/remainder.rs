@@ -1,13 +1,9 @@
-use anyhow::Result;
-use serde_json::Value;
+use crate::{util::value_to_number, value::JsValue};
 
-use crate::util::{number_from_f64, value_to_number};
-
-pub fn remainder(left: Value, right: Value) -> Result<Value> {
-    let lnum = value_to_number(left)?;
-    let rnum = value_to_number(right)?;
-    match lnum {
-        0.0 => Ok(Value::Null),
-        lnum => Ok(Value::Number(number_from_f64(lnum % rnum)?)),
-    }
+pub fn remainder(left: JsValue, right: JsValue) -> JsValue {
+    let lnum = value_to_number(&left);
+    let rnum = value_to_number(&right);
+    // Rust's `%` is a truncating remainder like JavaScript's: the sign follows
+    // the dividend and a zero divisor gives NaN.
+    JsValue::Number(lnum % rnum)
 }
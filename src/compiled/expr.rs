@@ -0,0 +1,188 @@
+use std::sync::Arc;
+
+use oxc::{
+    ast::ast::{
+        AssignmentOperator, BinaryOperator, LogicalOperator, UnaryOperator, UpdateOperator,
+    },
+    span::Span,
+};
+
+use super::Stmt;
+use crate::regexp::RegExp;
+
+/// An owned expression node lowered from the oxc AST.
+///
+/// Unlike the oxc AST, this tree does not borrow from an `Allocator`, so it can
+/// be stored and evaluated any number of times.
+#[derive(Debug, Clone)]
+pub(crate) struct Expr {
+    pub span: Span,
+    pub kind: ExprKind,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum ExprKind {
+    Literal(Literal),
+    Identifier(String),
+    Array(Vec<ArrayElement>),
+    /// An arrow function or `function` expression.
+    Function(Arc<Function>),
+    /// `target = value` and the compound forms, such as `target += value`.
+    Assignment {
+        operator: AssignmentOperator,
+        target: AssignmentTarget,
+        value: Box<Expr>,
+    },
+    Binary {
+        operator: BinaryOperator,
+        left: Box<Expr>,
+        right: Box<Expr>,
+    },
+    Call {
+        callee: Box<Expr>,
+        callee_name: Option<String>,
+        arguments: Vec<Expr>,
+    },
+    /// The end of an optional chain such as `user?.address.city`. When a `?.`
+    /// inside it short-circuits, the whole chain is `undefined`, but nothing
+    /// outside it is skipped: `(user?.address).city` still fails for a
+    /// missing user.
+    Chain(Box<Expr>),
+    Conditional {
+        test: Box<Expr>,
+        consequent: Box<Expr>,
+        alternate: Box<Expr>,
+    },
+    Logical {
+        operator: LogicalOperator,
+        left: Box<Expr>,
+        right: Box<Expr>,
+    },
+    Object(Vec<Property>),
+    /// `a, b, c`. The expressions are evaluated from left to right and the
+    /// value of the last one is the result.
+    Sequence(Vec<Expr>),
+    StaticMember {
+        object: Box<Expr>,
+        property: String,
+        optional: bool,
+    },
+    ComputedMember {
+        object: Box<Expr>,
+        property: Box<Expr>,
+        optional: bool,
+    },
+    Template(Template),
+    TaggedTemplate {
+        tag: Box<Expr>,
+        template: Template,
+    },
+    Unary {
+        operator: UnaryOperator,
+        argument: Box<Expr>,
+    },
+    /// `++target`, `target--` and so on.
+    Update {
+        operator: UpdateOperator,
+        prefix: bool,
+        target: AssignmentTarget,
+    },
+    /// Syntax that parsed fine but cannot be evaluated. The error is raised
+    /// only when the node is reached, just like walking the oxc AST did.
+    Unsupported(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Literal {
+    Null,
+    Boolean(bool),
+    Number(f64),
+    String(String),
+    /// Each evaluation creates a new regular expression object, so two
+    /// evaluations are never `===`.
+    RegExp(RegExp),
+}
+
+/// A function expression. It is shared with every function value created
+/// from it while evaluating.
+#[derive(Debug)]
+pub(crate) struct Function {
+    /// The name of a named `function` expression, which refers to the
+    /// function inside its body.
+    pub name: Option<String>,
+    pub params: Vec<String>,
+    pub body: FunctionBody,
+}
+
+#[derive(Debug)]
+pub(crate) enum FunctionBody {
+    /// The expression of an arrow function such as `x => x * 2`, which is its
+    /// result.
+    Expression(Expr),
+    /// Statements, which result in `undefined` unless they `return`.
+    Block(Vec<Stmt>),
+}
+
+/// A template literal: `quasis` has one more element than `expressions`,
+/// and the two are interleaved, starting with `quasis`.
+#[derive(Debug, Clone)]
+pub(crate) struct Template {
+    pub quasis: Vec<TemplateElement>,
+    pub expressions: Vec<Expr>,
+}
+
+/// A string part of a template. Only tagged templates may contain invalid
+/// escapes, which leave `cooked` empty.
+#[derive(Debug, Clone)]
+pub(crate) struct TemplateElement {
+    pub raw: String,
+    pub cooked: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum ArrayElement {
+    Expr(Expr),
+    /// `...items`
+    Spread(Expr),
+    /// A hole, as in `[1, , 3]`. It reads as `undefined`.
+    Hole,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Property {
+    KeyValue {
+        key: PropertyKey,
+        value: Expr,
+    },
+    /// `...object`
+    Spread(Expr),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum PropertyKey {
+    /// A key written as an identifier, string or number, as in `{ a: 1 }`,
+    /// `{ "a": 1 }` or `{ 1: "a" }`.
+    Static(String),
+    /// A computed key, as in `{ [name]: 1 }`.
+    Computed(Expr),
+}
+
+/// What an assignment or update writes to.
+#[derive(Debug, Clone)]
+pub(crate) enum AssignmentTarget {
+    Identifier(String),
+    StaticMember {
+        object: Box<Expr>,
+        property: String,
+    },
+    ComputedMember {
+        object: Box<Expr>,
+        property: Box<Expr>,
+    },
+}
+
+impl Expr {
+    pub fn new(span: Span, kind: ExprKind) -> Self {
+        Self { span, kind }
+    }
+}
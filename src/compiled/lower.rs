@@ -0,0 +1,523 @@
+use crate::{
+    error::{EvaluatorError, ParseError, Result},
+    regexp::RegExp,
+    value::number_to_string,
+};
+use std::{collections::HashSet, sync::Arc};
+
+use oxc::{
+    ast::ast::{
+        self, Argument, ArrayExpression, ArrayExpressionElement, ArrowFunctionExpression,
+        BindingPatternKind, CallExpression, ChainElement, ComputedMemberExpression, Expression,
+        ForStatementLeft, FormalParameters, ObjectExpression, ObjectPropertyKind, Program,
+        PropertyKind, SimpleAssignmentTarget, Statement, StaticMemberExpression, TemplateLiteral,
+        VariableDeclarationKind,
+    },
+    span::{Atom, GetSpan, Span},
+};
+
+use super::{
+    expr::{
+        ArrayElement, AssignmentTarget, Expr, ExprKind, Function, FunctionBody, Literal, Property,
+        PropertyKey, Template, TemplateElement,
+    },
+    stmt::{Stmt, StmtKind},
+};
+
+/// Lowers a parsed program made of a single expression into an owned [`Expr`].
+pub(crate) fn lower_program(program: &Program) -> Result<Expr> {
+    let mut statements = program
+        .body
+        .iter()
+        .filter(|stmt| !matches!(stmt, Statement::EmptyStatement(_)));
+    let first = statements.next();
+    // Directives such as `"a";` are statements too.
+    let spans = program.directives.iter().map(|directive| directive.span);
+    let mut spans = spans
+        .chain(first.map(GetSpan::span))
+        .chain(statements.map(GetSpan::span));
+    if let Some(span) = spans.nth(1) {
+        return Err(EvaluatorError::unsupported(
+            "Expected a single expression, use Evaluator::evaluate_script to run statements",
+        )
+        .with_span(span));
+    }
+    match first {
+        Some(Statement::ExpressionStatement(stmt)) => lower_expr(&stmt.expression),
+        Some(stmt) => Err(EvaluatorError::unsupported(format!(
+            "Unsupported statement: {:?}",
+            stmt
+        ))
+        .with_span(stmt.span())),
+        None => {
+            if let Some(directive) = program.directives.first() {
+                return Ok(Expr::new(
+                    directive.span,
+                    ExprKind::Literal(Literal::String(directive.directive.into_string())),
+                ));
+            }
+            Err(EvaluatorError::unsupported("No statements found").with_span(program.span))
+        }
+    }
+}
+
+pub(crate) fn lower_expr(expr: &Expression) -> Result<Expr> {
+    let span = expr.span();
+    let kind = match expr {
+        Expression::BooleanLiteral(expr) => ExprKind::Literal(Literal::Boolean(expr.value)),
+        Expression::NullLiteral(_) => ExprKind::Literal(Literal::Null),
+        Expression::NumericLiteral(expr) => ExprKind::Literal(Literal::Number(expr.value)),
+        Expression::StringLiteral(expr) => {
+            ExprKind::Literal(Literal::String(expr.value.into_string()))
+        }
+        Expression::RegExpLiteral(expr) => {
+            match RegExp::new(
+                &expr.regex.pattern.to_string(),
+                &expr.regex.flags.to_string(),
+            ) {
+                Ok(regexp) => ExprKind::Literal(Literal::RegExp(regexp)),
+                Err(e) => ExprKind::Unsupported(e.to_string()),
+            }
+        }
+        Expression::Identifier(expr) => ExprKind::Identifier(expr.name.to_string()),
+        Expression::ArrayExpression(expr) => return lower_array(expr),
+        Expression::ArrowFunctionExpression(expr) => return lower_arrow(expr),
+        Expression::FunctionExpression(expr) => return lower_function(expr),
+        Expression::AssignmentExpression(expr) => {
+            let Some(target) = expr.left.as_simple_assignment_target() else {
+                return Ok(unsupported(
+                    expr.left.span(),
+                    "Unsupported assignment target",
+                    &expr.left,
+                ));
+            };
+            ExprKind::Assignment {
+                operator: expr.operator,
+                target: match lower_assignment_target(target)? {
+                    Ok(target) => target,
+                    Err(unsupported) => return Ok(unsupported),
+                },
+                value: Box::new(lower_expr(&expr.right)?),
+            }
+        }
+        Expression::BinaryExpression(expr) => ExprKind::Binary {
+            operator: expr.operator,
+            left: Box::new(lower_expr(&expr.left)?),
+            right: Box::new(lower_expr(&expr.right)?),
+        },
+        Expression::CallExpression(expr) => return lower_call(expr),
+        Expression::ChainExpression(expr) => ExprKind::Chain(Box::new(match &expr.expression {
+            ChainElement::CallExpression(expr) => lower_call(expr)?,
+            ChainElement::StaticMemberExpression(expr) => lower_static_member(expr)?,
+            ChainElement::ComputedMemberExpression(expr) => lower_computed_member(expr)?,
+            element => return Ok(unsupported(span, "Unsupported ChainExpression", element)),
+        })),
+        Expression::ComputedMemberExpression(expr) => return lower_computed_member(expr),
+        Expression::ConditionalExpression(expr) => ExprKind::Conditional {
+            test: Box::new(lower_expr(&expr.test)?),
+            consequent: Box::new(lower_expr(&expr.consequent)?),
+            alternate: Box::new(lower_expr(&expr.alternate)?),
+        },
+        Expression::LogicalExpression(expr) => ExprKind::Logical {
+            operator: expr.operator,
+            left: Box::new(lower_expr(&expr.left)?),
+            right: Box::new(lower_expr(&expr.right)?),
+        },
+        Expression::ObjectExpression(expr) => return lower_object(expr),
+        Expression::ParenthesizedExpression(expr) => return lower_expr(&expr.expression),
+        Expression::SequenceExpression(expr) => ExprKind::Sequence(
+            expr.expressions
+                .iter()
+                .map(lower_expr)
+                .collect::<Result<_>>()?,
+        ),
+        Expression::StaticMemberExpression(expr) => return lower_static_member(expr),
+        Expression::TaggedTemplateExpression(expr) => ExprKind::TaggedTemplate {
+            tag: Box::new(lower_expr(&expr.tag)?),
+            template: lower_template(&expr.quasi)?,
+        },
+        Expression::TemplateLiteral(expr) => ExprKind::Template(lower_template(expr)?),
+        Expression::UnaryExpression(expr) => ExprKind::Unary {
+            operator: expr.operator,
+            argument: Box::new(lower_expr(&expr.argument)?),
+        },
+        Expression::UpdateExpression(expr) => ExprKind::Update {
+            operator: expr.operator,
+            prefix: expr.prefix,
+            target: match lower_assignment_target(&expr.argument)? {
+                Ok(target) => target,
+                Err(unsupported) => return Ok(unsupported),
+            },
+        },
+        _ => return Ok(unsupported(span, "Unsupported expression", expr)),
+    };
+    Ok(Expr::new(span, kind))
+}
+
+/// Lowers every statement of a parsed script. Directives such as `"a";` are
+/// kept as expression statements.
+pub(crate) fn lower_script(program: &Program) -> Result<Vec<Stmt>> {
+    let mut statements = program
+        .directives
+        .iter()
+        .map(|directive| {
+            let value = Literal::String(directive.directive.into_string());
+            let expr = Expr::new(directive.span, ExprKind::Literal(value));
+            Stmt::new(directive.span, StmtKind::Expression(expr))
+        })
+        .collect::<Vec<Stmt>>();
+    statements.extend(lower_block(&program.body)?);
+    Ok(statements)
+}
+
+fn lower_block(statements: &[Statement]) -> Result<Vec<Stmt>> {
+    lower_body(statements, &[])
+}
+
+/// Lowers the statements of a block or function body, whose `params` are
+/// declared in the same scope. Declaring a name twice in the same scope is
+/// reported like a parse error, as JavaScript does before running anything.
+fn lower_body(statements: &[Statement], params: &[String]) -> Result<Vec<Stmt>> {
+    let mut declared = params.iter().map(String::as_str).collect::<HashSet<&str>>();
+    for statement in statements {
+        let Statement::VariableDeclaration(decl) = statement else {
+            continue;
+        };
+        for declarator in &decl.declarations {
+            let BindingPatternKind::BindingIdentifier(ident) = &declarator.id.kind else {
+                continue;
+            };
+            if !declared.insert(ident.name.as_str()) {
+                let error = ParseError {
+                    message: format!("Identifier '{}' has already been declared", ident.name),
+                    span: ident.span,
+                    help: None,
+                };
+                return Err(EvaluatorError::Parse {
+                    errors: vec![error],
+                    span: ident.span,
+                });
+            }
+        }
+    }
+    statements.iter().map(lower_statement).collect()
+}
+
+fn lower_statement(statement: &Statement) -> Result<Stmt> {
+    let span = statement.span();
+    let kind = match statement {
+        Statement::ExpressionStatement(stmt) => StmtKind::Expression(lower_expr(&stmt.expression)?),
+        Statement::VariableDeclaration(decl) => {
+            let Some(constant) = is_constant(decl.kind) else {
+                return Ok(unsupported_statement(span, "Unsupported declaration", decl));
+            };
+            let mut declarators = Vec::with_capacity(decl.declarations.len());
+            for declarator in &decl.declarations {
+                let BindingPatternKind::BindingIdentifier(ident) = &declarator.id.kind else {
+                    return Ok(unsupported_statement(
+                        declarator.span,
+                        "Unsupported binding",
+                        declarator,
+                    ));
+                };
+                let init = declarator.init.as_ref().map(lower_expr).transpose()?;
+                declarators.push((ident.name.to_string(), init));
+            }
+            StmtKind::Declaration {
+                constant,
+                declarators,
+            }
+        }
+        Statement::BlockStatement(block) => StmtKind::Block(lower_block(&block.body)?),
+        Statement::IfStatement(stmt) => StmtKind::If {
+            test: lower_expr(&stmt.test)?,
+            consequent: Box::new(lower_statement(&stmt.consequent)?),
+            alternate: match &stmt.alternate {
+                Some(alternate) => Some(Box::new(lower_statement(alternate)?)),
+                None => None,
+            },
+        },
+        Statement::ForOfStatement(stmt) if stmt.r#await => {
+            return Ok(unsupported_statement(span, "Unsupported for await", stmt))
+        }
+        Statement::ForOfStatement(stmt) => {
+            let ForStatementLeft::VariableDeclaration(decl) = &stmt.left else {
+                return Ok(unsupported_statement(
+                    span,
+                    "Unsupported for...of target",
+                    &stmt.left,
+                ));
+            };
+            let binding =
+                decl.declarations
+                    .first()
+                    .and_then(|declarator| match &declarator.id.kind {
+                        BindingPatternKind::BindingIdentifier(ident) => {
+                            Some(ident.name.to_string())
+                        }
+                        _ => None,
+                    });
+            let (Some(constant), Some(binding)) = (is_constant(decl.kind), binding) else {
+                return Ok(unsupported_statement(
+                    decl.span,
+                    "Unsupported for...of target",
+                    decl,
+                ));
+            };
+            StmtKind::ForOf {
+                constant,
+                binding,
+                iterable: lower_expr(&stmt.right)?,
+                body: Box::new(lower_statement(&stmt.body)?),
+            }
+        }
+        Statement::ReturnStatement(stmt) => {
+            StmtKind::Return(stmt.argument.as_ref().map(lower_expr).transpose()?)
+        }
+        Statement::EmptyStatement(_) => StmtKind::Empty,
+        statement => {
+            return Ok(unsupported_statement(
+                span,
+                "Unsupported statement",
+                statement,
+            ))
+        }
+    };
+    Ok(Stmt::new(span, kind))
+}
+
+/// Whether a `let` or `const` declaration is constant. Other kinds of
+/// declarations are not supported.
+fn is_constant(kind: VariableDeclarationKind) -> Option<bool> {
+    match kind {
+        VariableDeclarationKind::Let => Some(false),
+        VariableDeclarationKind::Const => Some(true),
+        _ => None,
+    }
+}
+
+fn lower_array(expr: &ArrayExpression) -> Result<Expr> {
+    let elements = expr
+        .elements
+        .iter()
+        .map(|element| {
+            Ok(match element {
+                ArrayExpressionElement::SpreadElement(spread) => {
+                    ArrayElement::Spread(lower_expr(&spread.argument)?)
+                }
+                ArrayExpressionElement::Elision(_) => ArrayElement::Hole,
+                element => ArrayElement::Expr(lower_expr(element.to_expression())?),
+            })
+        })
+        .collect::<Result<_>>()?;
+    Ok(Expr::new(expr.span, ExprKind::Array(elements)))
+}
+
+fn lower_object(expr: &ObjectExpression) -> Result<Expr> {
+    let mut properties = Vec::with_capacity(expr.properties.len());
+    for property in &expr.properties {
+        let property = match property {
+            ObjectPropertyKind::SpreadProperty(spread) => {
+                Property::Spread(lower_expr(&spread.argument)?)
+            }
+            ObjectPropertyKind::ObjectProperty(property) if property.kind != PropertyKind::Init => {
+                return Ok(unsupported(
+                    property.span,
+                    "Unsupported accessor property",
+                    property,
+                ))
+            }
+            ObjectPropertyKind::ObjectProperty(property) => {
+                let key = match &property.key {
+                    key if property.computed => match key.as_expression() {
+                        Some(key) => PropertyKey::Computed(lower_expr(key)?),
+                        None => {
+                            return Ok(unsupported(key.span(), "Unsupported property key", key))
+                        }
+                    },
+                    // Numeric keys are written the way JavaScript prints the
+                    // number, so `{ 1.50: x }` has the key "1.5".
+                    ast::PropertyKey::NumericLiteral(number) => {
+                        PropertyKey::Static(number_to_string(number.value))
+                    }
+                    key @ (ast::PropertyKey::StaticIdentifier(_)
+                    | ast::PropertyKey::StringLiteral(_)) => {
+                        PropertyKey::Static(key.static_name().unwrap_or_default().into_owned())
+                    }
+                    key => return Ok(unsupported(key.span(), "Unsupported property key", key)),
+                };
+                Property::KeyValue {
+                    key,
+                    value: lower_expr(&property.value)?,
+                }
+            }
+        };
+        properties.push(property);
+    }
+    Ok(Expr::new(expr.span, ExprKind::Object(properties)))
+}
+
+/// Lowers the target of an assignment or update, or returns the
+/// [`ExprKind::Unsupported`] node to use instead.
+fn lower_assignment_target(
+    target: &SimpleAssignmentTarget,
+) -> Result<std::result::Result<AssignmentTarget, Expr>> {
+    Ok(match target {
+        SimpleAssignmentTarget::AssignmentTargetIdentifier(ident) => {
+            Ok(AssignmentTarget::Identifier(ident.name.to_string()))
+        }
+        SimpleAssignmentTarget::StaticMemberExpression(expr) => {
+            Ok(AssignmentTarget::StaticMember {
+                object: Box::new(lower_expr(&expr.object)?),
+                property: expr.property.name.to_string(),
+            })
+        }
+        SimpleAssignmentTarget::ComputedMemberExpression(expr) => {
+            Ok(AssignmentTarget::ComputedMember {
+                object: Box::new(lower_expr(&expr.object)?),
+                property: Box::new(lower_expr(&expr.expression)?),
+            })
+        }
+        target => Err(unsupported(
+            target.span(),
+            "Unsupported assignment target",
+            target,
+        )),
+    })
+}
+
+fn lower_template(template: &TemplateLiteral) -> Result<Template> {
+    Ok(Template {
+        quasis: template
+            .quasis
+            .iter()
+            .map(|quasi| TemplateElement {
+                raw: quasi.value.raw.to_string(),
+                cooked: quasi.value.cooked.map(Atom::into_string),
+            })
+            .collect(),
+        expressions: template
+            .expressions
+            .iter()
+            .map(lower_expr)
+            .collect::<Result<_>>()?,
+    })
+}
+
+fn lower_arrow(expr: &ArrowFunctionExpression) -> Result<Expr> {
+    if expr.r#async {
+        return Ok(unsupported(
+            expr.span,
+            "Unsupported async arrow function",
+            expr,
+        ));
+    }
+    let params = match lower_params(&expr.params) {
+        Ok(params) => params,
+        Err(unsupported) => return Ok(unsupported),
+    };
+    let body = match expr.get_expression() {
+        Some(body) => FunctionBody::Expression(lower_expr(body)?),
+        None => FunctionBody::Block(lower_body(&expr.body.statements, &params)?),
+    };
+    let function = Function {
+        name: None,
+        params,
+        body,
+    };
+    Ok(Expr::new(expr.span, ExprKind::Function(Arc::new(function))))
+}
+
+fn lower_function(expr: &ast::Function) -> Result<Expr> {
+    if expr.r#async || expr.generator {
+        return Ok(unsupported(
+            expr.span,
+            "Unsupported async or generator function",
+            expr,
+        ));
+    }
+    let params = match lower_params(&expr.params) {
+        Ok(params) => params,
+        Err(unsupported) => return Ok(unsupported),
+    };
+    let body = match &expr.body {
+        Some(body) => lower_body(&body.statements, &params)?,
+        None => Vec::new(),
+    };
+    let function = Function {
+        name: expr.id.as_ref().map(|id| id.name.to_string()),
+        params,
+        body: FunctionBody::Block(body),
+    };
+    Ok(Expr::new(expr.span, ExprKind::Function(Arc::new(function))))
+}
+
+/// Returns the names of plain parameters, or the [`ExprKind::Unsupported`] node
+/// to use instead of the function.
+fn lower_params(params: &FormalParameters) -> std::result::Result<Vec<String>, Expr> {
+    let mut names = Vec::with_capacity(params.items.len());
+    for param in &params.items {
+        match &param.pattern.kind {
+            BindingPatternKind::BindingIdentifier(ident) => names.push(ident.name.to_string()),
+            _ => return Err(unsupported(param.span, "Unsupported parameter", param)),
+        }
+    }
+    if let Some(rest) = &params.rest {
+        return Err(unsupported(rest.span, "Unsupported rest parameter", rest));
+    }
+    Ok(names)
+}
+
+fn lower_call(expr: &CallExpression) -> Result<Expr> {
+    let mut arguments = Vec::with_capacity(expr.arguments.len());
+    for argument in &expr.arguments {
+        match argument {
+            Argument::SpreadElement(_) => {
+                return Ok(unsupported(
+                    argument.span(),
+                    "Unsupported argument",
+                    argument,
+                ))
+            }
+            argument => arguments.push(lower_expr(argument.to_expression())?),
+        }
+    }
+    let kind = ExprKind::Call {
+        callee: Box::new(lower_expr(&expr.callee)?),
+        callee_name: expr.callee_name().map(str::to_string),
+        arguments,
+    };
+    Ok(Expr::new(expr.span, kind))
+}
+
+fn lower_static_member(expr: &StaticMemberExpression) -> Result<Expr> {
+    let kind = ExprKind::StaticMember {
+        object: Box::new(lower_expr(&expr.object)?),
+        property: expr.property.name.to_string(),
+        optional: expr.optional,
+    };
+    Ok(Expr::new(expr.span, kind))
+}
+
+fn lower_computed_member(expr: &ComputedMemberExpression) -> Result<Expr> {
+    let kind = ExprKind::ComputedMember {
+        object: Box::new(lower_expr(&expr.object)?),
+        property: Box::new(lower_expr(&expr.expression)?),
+        optional: expr.optional,
+    };
+    Ok(Expr::new(expr.span, kind))
+}
+
+fn unsupported<T: std::fmt::Debug>(span: Span, message: &str, node: &T) -> Expr {
+    Expr::new(
+        span,
+        ExprKind::Unsupported(format!("{}: {:?}", message, node)),
+    )
+}
+
+fn unsupported_statement<T: std::fmt::Debug>(span: Span, message: &str, node: &T) -> Stmt {
+    Stmt::new(
+        span,
+        StmtKind::Unsupported(format!("{}: {:?}", message, node)),
+    )
+}
@@ -0,0 +1,114 @@
+mod expr;
+mod lower;
+mod stmt;
+
+pub(crate) use self::expr::*;
+pub(crate) use self::stmt::*;
+
+use oxc::{
+    allocator::Allocator,
+    ast::ast::Program,
+    diagnostics::OxcDiagnostic,
+    parser::{ParseOptions, Parser},
+    span::{SourceType, Span},
+};
+
+use crate::error::{EvaluatorError, ParseError, Result};
+
+/// An expression that has been parsed once and can be evaluated many times.
+///
+/// A `CompiledExpression` owns its syntax tree, so it does not borrow from the
+/// source string or the parser's allocator. Create one with
+/// [`Evaluator::compile`](crate::Evaluator::compile) and run it with
+/// [`Evaluator::evaluate_compiled`](crate::Evaluator::evaluate_compiled).
+#[derive(Debug, Clone)]
+pub struct CompiledExpression {
+    source: String,
+    pub(crate) expr: Expr,
+}
+
+impl CompiledExpression {
+    pub(crate) fn parse(source: &str) -> Result<Self> {
+        let expr = parse(source, ParseOptions::default(), lower::lower_program)?;
+        Ok(Self {
+            source: source.to_string(),
+            expr,
+        })
+    }
+
+    /// Returns the source text this expression was compiled from.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+/// A script that has been parsed once and can be run many times.
+///
+/// Create one with [`Evaluator::compile_script`](crate::Evaluator::compile_script)
+/// and run it with
+/// [`Evaluator::evaluate_compiled_script`](crate::Evaluator::evaluate_compiled_script).
+#[derive(Debug, Clone)]
+pub struct CompiledScript {
+    source: String,
+    pub(crate) span: Span,
+    pub(crate) statements: Vec<Stmt>,
+}
+
+impl CompiledScript {
+    pub(crate) fn parse(source: &str) -> Result<Self> {
+        // A script runs like the body of a function, so it may `return`.
+        let options = ParseOptions {
+            allow_return_outside_function: true,
+            ..ParseOptions::default()
+        };
+        let statements = parse(source, options, lower::lower_script)?;
+        Ok(Self {
+            source: source.to_string(),
+            span: Span::new(0, source.len() as u32),
+            statements,
+        })
+    }
+
+    /// Returns the source text this script was compiled from.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+/// Parses `source` and lowers the program with `lower`.
+fn parse<T>(
+    source: &str,
+    options: ParseOptions,
+    lower: impl FnOnce(&Program) -> Result<T>,
+) -> Result<T> {
+    let allocator = Allocator::default();
+    let parser = Parser::new(&allocator, source, SourceType::cjs()).with_options(options);
+    let parsed = parser.parse();
+    if !parsed.errors.is_empty() {
+        let errors = parsed
+            .errors
+            .iter()
+            .map(parse_error)
+            .collect::<Vec<ParseError>>();
+        let span = errors[0].span;
+        return Err(EvaluatorError::Parse { errors, span });
+    }
+    lower(&parsed.program)
+}
+
+fn parse_error(diagnostic: &OxcDiagnostic) -> ParseError {
+    let label = diagnostic.labels.as_ref().and_then(|labels| {
+        labels
+            .iter()
+            .find(|label| label.primary())
+            .or_else(|| labels.first())
+    });
+    let span = label.map_or(Span::default(), |label| {
+        Span::new(label.offset() as u32, (label.offset() + label.len()) as u32)
+    });
+    ParseError {
+        message: diagnostic.message.to_string(),
+        span,
+        help: diagnostic.help.as_ref().map(|help| help.to_string()),
+    }
+}
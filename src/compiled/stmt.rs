@@ -0,0 +1,47 @@
+use oxc::span::Span;
+
+use super::Expr;
+
+/// An owned statement node of a script, lowered from the oxc AST.
+#[derive(Debug, Clone)]
+pub(crate) struct Stmt {
+    pub span: Span,
+    pub kind: StmtKind,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum StmtKind {
+    Expression(Expr),
+    /// `let` or `const` declarations. A declarator without an initializer
+    /// binds `undefined`.
+    Declaration {
+        constant: bool,
+        declarators: Vec<(String, Option<Expr>)>,
+    },
+    /// A block, whose declarations are only visible inside it.
+    Block(Vec<Stmt>),
+    If {
+        test: Expr,
+        consequent: Box<Stmt>,
+        alternate: Option<Box<Stmt>>,
+    },
+    /// `for (const binding of iterable) body`. Every iteration binds a new
+    /// variable.
+    ForOf {
+        constant: bool,
+        binding: String,
+        iterable: Expr,
+        body: Box<Stmt>,
+    },
+    Return(Option<Expr>),
+    Empty,
+    /// A statement that parsed fine but cannot be run. The error is raised only
+    /// when the statement is reached.
+    Unsupported(String),
+}
+
+impl Stmt {
+    pub fn new(span: Span, kind: StmtKind) -> Self {
+        Self { span, kind }
+    }
+}
@@ -1,7 +1,35 @@
 use serde_json::Value;
 
-type BoxFunction = Box<dyn Fn(Vec<Value>) -> Value>;
+/// Error type returned by fallible host functions.
+pub type HostError = Box<dyn std::error::Error + Send + Sync>;
+
+// Host functions must be `Send + Sync` so an `Evaluator` can be shared between threads.
+type BoxFunction = Box<dyn Fn(Vec<Value>) -> Value + Send + Sync>;
+type BoxFallibleFunction = Box<dyn Fn(Vec<Value>) -> Result<Value, HostError> + Send + Sync>;
+type BoxTagFunction =
+    Box<dyn Fn(TemplateStrings, Vec<Value>) -> Result<Value, HostError> + Send + Sync>;
+
+/// The string parts of a tagged template such as `` sql`id = ${id}` ``, which
+/// has the parts `"id = "` and `""` around one value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateStrings {
+    /// The parts with escape sequences processed. A part is `None` when it
+    /// contains an invalid escape, such as `\unicode`.
+    pub cooked: Vec<Option<String>>,
+    /// The parts exactly as written in the source.
+    pub raw: Vec<String>,
+}
+
 pub enum ContextEntry {
     Variable(Value),
     Function(BoxFunction),
+    /// A host function that can reject its input. Errors are returned from
+    /// [`Evaluator::evaluate`](crate::Evaluator::evaluate) as
+    /// [`EvaluatorError::HostFunction`](crate::EvaluatorError::HostFunction).
+    FallibleFunction(BoxFallibleFunction),
+    /// A tag function for tagged templates. It receives the string parts and
+    /// the interpolated values separately, so it decides how the values are
+    /// embedded, e.g. by escaping them. Errors are reported like those of a
+    /// [`ContextEntry::FallibleFunction`].
+    TagFunction(BoxTagFunction),
 }
@@ -1,3 +1,5 @@
 mod entry;
+mod provider;
 
 pub use self::entry::*;
+pub use self::provider::*;
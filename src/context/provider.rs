@@ -0,0 +1,89 @@
+use std::{borrow::Cow, collections::HashMap, sync::Arc};
+
+use serde_json::Value;
+
+use super::ContextEntry;
+
+/// Resolves identifiers for an [`Evaluator`](crate::Evaluator) on demand.
+///
+/// The evaluator asks its provider for a variable or function each time an
+/// identifier is evaluated, so implementations can fetch values lazily, fall
+/// back to other lookups, or combine several sources. `HashMap<String,
+/// ContextEntry>` is the simplest implementation, and `Vec<P>` tries each
+/// provider in order.
+pub trait ContextProvider: Send + Sync {
+    /// Returns the value of the variable `name`, or `None` if it is not defined.
+    fn variable(&self, name: &str) -> Option<Cow<'_, Value>>;
+
+    /// Returns the host function registered as `name`.
+    ///
+    /// Only [`ContextEntry::Function`], [`ContextEntry::FallibleFunction`] and
+    /// [`ContextEntry::TagFunction`] entries are callable; any other entry is
+    /// treated as missing.
+    fn function(&self, name: &str) -> Option<&ContextEntry> {
+        let _ = name;
+        None
+    }
+}
+
+impl ContextProvider for HashMap<String, ContextEntry> {
+    fn variable(&self, name: &str) -> Option<Cow<'_, Value>> {
+        match self.get(name) {
+            Some(ContextEntry::Variable(value)) => Some(Cow::Borrowed(value)),
+            _ => None,
+        }
+    }
+
+    fn function(&self, name: &str) -> Option<&ContextEntry> {
+        self.get(name)
+            .filter(|entry| !matches!(entry, ContextEntry::Variable(_)))
+    }
+}
+
+/// Plain variables, e.g. a per-call overlay for
+/// [`Evaluator::evaluate_with`](crate::Evaluator::evaluate_with).
+impl ContextProvider for HashMap<String, Value> {
+    fn variable(&self, name: &str) -> Option<Cow<'_, Value>> {
+        self.get(name).map(Cow::Borrowed)
+    }
+}
+
+impl<P: ContextProvider> ContextProvider for Vec<P> {
+    fn variable(&self, name: &str) -> Option<Cow<'_, Value>> {
+        self.iter().find_map(|provider| provider.variable(name))
+    }
+
+    fn function(&self, name: &str) -> Option<&ContextEntry> {
+        self.iter().find_map(|provider| provider.function(name))
+    }
+}
+
+impl<P: ContextProvider + ?Sized> ContextProvider for &P {
+    fn variable(&self, name: &str) -> Option<Cow<'_, Value>> {
+        (**self).variable(name)
+    }
+
+    fn function(&self, name: &str) -> Option<&ContextEntry> {
+        (**self).function(name)
+    }
+}
+
+impl<P: ContextProvider + ?Sized> ContextProvider for Box<P> {
+    fn variable(&self, name: &str) -> Option<Cow<'_, Value>> {
+        (**self).variable(name)
+    }
+
+    fn function(&self, name: &str) -> Option<&ContextEntry> {
+        (**self).function(name)
+    }
+}
+
+impl<P: ContextProvider + ?Sized> ContextProvider for Arc<P> {
+    fn variable(&self, name: &str) -> Option<Cow<'_, Value>> {
+        (**self).variable(name)
+    }
+
+    fn function(&self, name: &str) -> Option<&ContextEntry> {
+        (**self).function(name)
+    }
+}
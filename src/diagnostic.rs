@@ -0,0 +1,134 @@
+use std::fmt;
+
+use oxc::span::Span;
+
+use crate::error::EvaluatorError;
+
+/// A located, human readable description of an error in an expression.
+///
+/// Displaying a `Diagnostic` renders the offending source line with the span
+/// underlined by carets:
+///
+/// ```text
+/// error: Variable not found: missing
+///   --> 1:5
+///   |
+/// 1 | 1 + missing
+///   |     ^^^^^^^
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub message: String,
+    pub span: Span,
+    /// 1-based line of the start of `span`.
+    pub line: usize,
+    /// 1-based column of the start of `span`, counted in characters.
+    pub column: usize,
+    pub help: Option<String>,
+    source_line: String,
+    underline: usize,
+}
+
+impl Diagnostic {
+    pub fn new(source: &str, message: impl Into<String>, span: Span, help: Option<String>) -> Self {
+        let start = clamp_to_char_boundary(source, span.start as usize);
+        let end = clamp_to_char_boundary(source, (span.end as usize).max(start));
+
+        let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[start..]
+            .find('\n')
+            .map_or(source.len(), |i| start + i);
+        let line = source[..start].matches('\n').count() + 1;
+        let column = source[line_start..start].chars().count() + 1;
+        let underline = source[start..end.min(line_end)].chars().count().max(1);
+
+        Self {
+            message: message.into(),
+            span,
+            line,
+            column,
+            help,
+            source_line: source[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string(),
+            underline,
+        }
+    }
+
+    /// Renders the diagnostic as a source snippet with a caret underline.
+    pub fn render(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gutter = " ".repeat(self.line.to_string().len());
+        writeln!(f, "error: {}", self.message)?;
+        writeln!(f, "{} --> {}:{}", gutter, self.line, self.column)?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", self.line, self.source_line)?;
+        write!(
+            f,
+            "{} | {}{}",
+            gutter,
+            " ".repeat(self.column - 1),
+            "^".repeat(self.underline)
+        )?;
+        if let Some(help) = &self.help {
+            write!(f, "\n{} = help: {}", gutter, help)?;
+        }
+        Ok(())
+    }
+}
+
+impl EvaluatorError {
+    /// Converts the error into diagnostics located in `source`, the text the
+    /// failing expression was compiled from.
+    ///
+    /// Parse errors produce one diagnostic per error reported by the parser;
+    /// every other error produces exactly one.
+    pub fn diagnostics(&self, source: &str) -> Vec<Diagnostic> {
+        match self {
+            EvaluatorError::Parse { errors, .. } => errors
+                .iter()
+                .map(|e| Diagnostic::new(source, e.message.clone(), e.span, e.help.clone()))
+                .collect(),
+            _ => vec![Diagnostic::new(
+                source,
+                self.to_string(),
+                self.span(),
+                self.help(),
+            )],
+        }
+    }
+
+    fn help(&self) -> Option<String> {
+        match self {
+            EvaluatorError::VariableNotFound { .. } => {
+                Some("add the variable to the context, or use `?.` if it is optional".into())
+            }
+            EvaluatorError::PropertyNotFound { .. } => {
+                Some("use `?.` to read a property that may be missing".into())
+            }
+            EvaluatorError::IndexOutOfRange { .. } => {
+                Some("use `?.[]` to read an index that may be missing".into())
+            }
+            EvaluatorError::UnknownFunction { .. } => {
+                Some("register the function in the context".into())
+            }
+            EvaluatorError::CallDepthExceeded { .. } => {
+                Some("make sure recursive functions reach a base case".into())
+            }
+            _ => None,
+        }
+    }
+}
+
+fn clamp_to_char_boundary(source: &str, mut index: usize) -> usize {
+    index = index.min(source.len());
+    while !source.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
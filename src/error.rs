@@ -1,16 +1,268 @@
+use oxc::span::Span;
+
+use crate::context::HostError;
+
+pub type Result<T, E = EvaluatorError> = std::result::Result<T, E>;
+
+/// A single error reported by the parser.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    pub span: Span,
+    pub help: Option<String>,
+}
+
+/// Errors returned while compiling or evaluating an expression.
+///
+/// Every variant carries the byte span of the source text it refers to. Errors
+/// raised by helpers that don't know where they were called from use an empty
+/// span until the evaluator fills it in with the offending node's span.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum EvaluatorError {
-    VariableNotFound(String),
-    PropertyNotFound(String, String),
+    /// The source text is not valid JavaScript. Holds every parser error; `span`
+    /// points at the first one.
+    Parse {
+        errors: Vec<ParseError>,
+        span: Span,
+    },
+    /// The syntax is valid JavaScript but the evaluator does not support it.
+    UnsupportedSyntax {
+        message: String,
+        span: Span,
+    },
+    VariableNotFound {
+        name: String,
+        span: Span,
+    },
+    PropertyNotFound {
+        object: String,
+        property: String,
+        span: Span,
+    },
+    /// An array or string was indexed past its end. `index` is the index as
+    /// written, so it may be negative.
+    IndexOutOfRange {
+        index: i64,
+        length: usize,
+        span: Span,
+    },
+    /// No context function, built-in method or math function matches `name`.
+    UnknownFunction {
+        name: String,
+        span: Span,
+    },
+    InvalidArgumentCount {
+        function: String,
+        expected: String,
+        found: usize,
+        span: Span,
+    },
+    /// Argument `index` (zero based) of `function` has the wrong type.
+    InvalidArgumentType {
+        function: String,
+        index: usize,
+        expected: String,
+        span: Span,
+    },
+    /// An operation was applied to a value that doesn't support it, such as
+    /// reading a property of `null`.
+    TypeError {
+        message: String,
+        span: Span,
+    },
+    /// A value could not be converted to the type an operation needed.
+    TypeCoercion {
+        message: String,
+        span: Span,
+    },
+    /// Function values called each other, or themselves, more deeply than
+    /// [`Evaluator::with_max_call_depth`](crate::Evaluator::with_max_call_depth)
+    /// allows.
+    CallDepthExceeded {
+        limit: usize,
+        span: Span,
+    },
+    /// A [`ContextEntry::FallibleFunction`](crate::context::ContextEntry::FallibleFunction)
+    /// returned an error. The original error is available as `source`.
+    HostFunction {
+        name: String,
+        source: HostError,
+        span: Span,
+    },
+}
+
+impl EvaluatorError {
+    /// Returns the byte span of the source text this error refers to.
+    pub fn span(&self) -> Span {
+        match self {
+            EvaluatorError::Parse { span, .. }
+            | EvaluatorError::UnsupportedSyntax { span, .. }
+            | EvaluatorError::VariableNotFound { span, .. }
+            | EvaluatorError::PropertyNotFound { span, .. }
+            | EvaluatorError::IndexOutOfRange { span, .. }
+            | EvaluatorError::UnknownFunction { span, .. }
+            | EvaluatorError::InvalidArgumentCount { span, .. }
+            | EvaluatorError::InvalidArgumentType { span, .. }
+            | EvaluatorError::TypeError { span, .. }
+            | EvaluatorError::TypeCoercion { span, .. }
+            | EvaluatorError::CallDepthExceeded { span, .. }
+            | EvaluatorError::HostFunction { span, .. } => *span,
+        }
+    }
+
+    /// Sets the span if it hasn't been set yet.
+    pub(crate) fn with_span(mut self, node_span: Span) -> Self {
+        match &mut self {
+            EvaluatorError::Parse { span, .. }
+            | EvaluatorError::UnsupportedSyntax { span, .. }
+            | EvaluatorError::VariableNotFound { span, .. }
+            | EvaluatorError::PropertyNotFound { span, .. }
+            | EvaluatorError::IndexOutOfRange { span, .. }
+            | EvaluatorError::UnknownFunction { span, .. }
+            | EvaluatorError::InvalidArgumentCount { span, .. }
+            | EvaluatorError::InvalidArgumentType { span, .. }
+            | EvaluatorError::TypeError { span, .. }
+            | EvaluatorError::TypeCoercion { span, .. }
+            | EvaluatorError::CallDepthExceeded { span, .. }
+            | EvaluatorError::HostFunction { span, .. } => {
+                if span.is_empty() {
+                    *span = node_span;
+                }
+            }
+        }
+        self
+    }
+
+    pub(crate) fn unsupported(message: impl Into<String>) -> Self {
+        EvaluatorError::UnsupportedSyntax {
+            message: message.into(),
+            span: Span::default(),
+        }
+    }
+
+    pub(crate) fn unknown_function(name: impl Into<String>) -> Self {
+        EvaluatorError::UnknownFunction {
+            name: name.into(),
+            span: Span::default(),
+        }
+    }
+
+    #[cfg_attr(
+        not(any(feature = "string", feature = "array", feature = "semver-support")),
+        allow(dead_code)
+    )]
+    pub(crate) fn argument_count(
+        function: impl Into<String>,
+        expected: impl Into<String>,
+        found: usize,
+    ) -> Self {
+        EvaluatorError::InvalidArgumentCount {
+            function: function.into(),
+            expected: expected.into(),
+            found,
+            span: Span::default(),
+        }
+    }
+
+    #[cfg_attr(not(any(feature = "string", feature = "array")), allow(dead_code))]
+    pub(crate) fn argument_type(
+        function: impl Into<String>,
+        index: usize,
+        expected: impl Into<String>,
+    ) -> Self {
+        EvaluatorError::InvalidArgumentType {
+            function: function.into(),
+            index,
+            expected: expected.into(),
+            span: Span::default(),
+        }
+    }
+
+    pub(crate) fn type_error(message: impl Into<String>) -> Self {
+        EvaluatorError::TypeError {
+            message: message.into(),
+            span: Span::default(),
+        }
+    }
+
+    pub(crate) fn type_coercion(message: impl Into<String>) -> Self {
+        EvaluatorError::TypeCoercion {
+            message: message.into(),
+            span: Span::default(),
+        }
+    }
 }
 
 impl std::fmt::Display for EvaluatorError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            EvaluatorError::VariableNotFound(var) => write!(f, "Variable not found: {}", var),
-            EvaluatorError::PropertyNotFound(obj, prop) => {
-                write!(f, "Property '{}' not found in object '{}'", prop, obj)
+            EvaluatorError::Parse { errors, .. } => {
+                let messages = errors
+                    .iter()
+                    .map(|e| e.message.as_str())
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                write!(f, "Parsing error: {}", messages)
+            }
+            EvaluatorError::UnsupportedSyntax { message, .. } => write!(f, "{}", message),
+            EvaluatorError::VariableNotFound { name, .. } => {
+                write!(f, "Variable not found: {}", name)
             }
+            EvaluatorError::PropertyNotFound {
+                object, property, ..
+            } => {
+                write!(
+                    f,
+                    "Property '{}' not found in object '{}'",
+                    property, object
+                )
+            }
+            EvaluatorError::IndexOutOfRange { index, length, .. } => {
+                write!(f, "Index {} out of range for length {}", index, length)
+            }
+            EvaluatorError::UnknownFunction { name, .. } => {
+                write!(f, "{:?} not found in function context", name)
+            }
+            EvaluatorError::InvalidArgumentCount {
+                function,
+                expected,
+                found,
+                ..
+            } => write!(
+                f,
+                "{} requires {} argument(s), found {}",
+                function, expected, found
+            ),
+            EvaluatorError::InvalidArgumentType {
+                function,
+                index,
+                expected,
+                ..
+            } => write!(
+                f,
+                "Argument {} of {} must be {}",
+                index + 1,
+                function,
+                expected
+            ),
+            EvaluatorError::TypeError { message, .. } => write!(f, "{}", message),
+            EvaluatorError::TypeCoercion { message, .. } => write!(f, "{}", message),
+            EvaluatorError::CallDepthExceeded { limit, .. } => {
+                write!(f, "Maximum call depth of {} exceeded", limit)
+            }
+            EvaluatorError::HostFunction { name, source, .. } => {
+                write!(f, "Function {:?} failed: {}", name, source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EvaluatorError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EvaluatorError::HostFunction { source, .. } => Some(source.as_ref()),
+            _ => None,
         }
     }
 }
@@ -1,161 +1,599 @@
 mod bin_op;
+mod compiled;
 pub mod context;
-mod error;
+pub mod diagnostic;
+pub mod error;
 #[cfg(feature = "math")]
 mod math;
 #[cfg(any(feature = "string", feature = "array"))]
 mod method;
+mod regexp;
+mod scope;
 #[cfg(feature = "semver-support")]
 mod semver_wrapper;
 mod unary;
 mod util;
+mod value;
 
-use anyhow::{anyhow, bail, Result};
 use bin_op::{
-    addition, bitwise_operation, compare, division, equality, exponential, multiplication,
-    remainder, subtraction, unsigned_right_shift,
+    addition, bitwise_operation, compare, division, equality, exponential, has_property,
+    instance_of, multiplication, remainder, subtraction, unsigned_right_shift,
 };
-use context::ContextEntry;
-use oxc::{
-    allocator::Allocator,
-    ast::ast::{
-        ArrayExpression, BinaryExpression, BinaryOperator, CallExpression, ChainElement,
-        ChainExpression, ConditionalExpression, Expression, LogicalExpression, LogicalOperator,
-        ObjectExpression, ObjectPropertyKind, Statement, StaticMemberExpression, UnaryExpression,
-        UnaryOperator,
-    },
-    parser::Parser,
-    span::SourceType,
+use compiled::{
+    ArrayElement, AssignmentTarget, Expr, ExprKind, FunctionBody, Literal, Property, PropertyKey,
+    Stmt, StmtKind, Template,
 };
-use serde_json::{to_string, Map, Value};
-use std::collections::HashMap;
-use unary::{unary_bitwise_not, unary_negation, unary_plus};
-use util::number_from_f64;
+use context::{ContextEntry, ContextProvider, TemplateStrings};
+use error::Result;
+use oxc::ast::ast::{
+    AssignmentOperator, BinaryOperator, LogicalOperator, UnaryOperator, UpdateOperator,
+};
+use scope::{ContextChain, Scope};
+use serde_json::Value;
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+    rc::Rc,
+};
+use unary::{type_of, unary_bitwise_not, unary_negation, unary_plus};
+use util::{
+    iterate, own_properties, relative_index, set_property, value_to_bool, value_to_index,
+    value_to_number, value_to_string,
+};
+use value::{Closure, JsValue};
+
+pub use compiled::{CompiledExpression, CompiledScript};
+pub use diagnostic::Diagnostic;
+pub use error::EvaluatorError;
+pub use oxc::span::Span;
+pub use value::{NonFiniteOutput, OutputPolicy, UndefinedOutput};
 
-use crate::error::EvaluatorError;
+/// How deeply function values may call each other unless
+/// [`Evaluator::with_max_call_depth`] says otherwise.
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 64;
 
+/// Evaluates JavaScript expressions against a context of variables and host
+/// functions.
+///
+/// `Evaluator` is `Send + Sync`, so a single instance can be wrapped in an
+/// `Arc` and used from many threads at once.
 pub struct Evaluator {
-    context: HashMap<String, ContextEntry>,
+    context: Box<dyn ContextProvider>,
+    output_policy: OutputPolicy,
+    strict: bool,
+    max_call_depth: usize,
+}
+
+/// The result of [`Evaluator::evaluate_mut`]: the value of the expression and
+/// the variables as its assignments left them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evaluation {
+    pub value: Value,
+    pub variables: HashMap<String, Value>,
 }
 
 impl Evaluator {
     pub fn new(context: HashMap<String, ContextEntry>) -> Self {
-        Self { context }
+        Self::with_provider(context)
+    }
+
+    /// Creates an evaluator that resolves identifiers through `provider`.
+    pub fn with_provider(provider: impl ContextProvider + 'static) -> Self {
+        Self {
+            context: Box::new(provider),
+            output_policy: OutputPolicy::default(),
+            strict: false,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+        }
+    }
+
+    /// Sets how results without a JSON equivalent, such as `undefined` or
+    /// `NaN`, are returned. See [`OutputPolicy`].
+    pub fn with_output_policy(mut self, policy: OutputPolicy) -> Self {
+        self.output_policy = policy;
+        self
+    }
+
+    /// In strict mode, reading a property that a string, number or boolean
+    /// doesn't have, such as `"abc".foo`, is an error instead of `undefined`.
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Sets how deeply function values may call each other, or themselves,
+    /// before evaluation fails with [`EvaluatorError::CallDepthExceeded`].
+    /// Defaults to [`DEFAULT_MAX_CALL_DEPTH`].
+    ///
+    /// Every call uses native stack, so a much higher limit may need a thread
+    /// with a larger stack.
+    pub fn with_max_call_depth(mut self, depth: usize) -> Self {
+        self.max_call_depth = depth;
+        self
+    }
+
+    /// Parses `expression` once so it can be evaluated repeatedly with
+    /// [`Evaluator::evaluate_compiled`].
+    pub fn compile(expression: &str) -> Result<CompiledExpression> {
+        CompiledExpression::parse(expression)
     }
 
     pub fn evaluate(&self, expression: &str) -> Result<Value> {
-        let allocator = Allocator::default();
-        let parser = Parser::new(&allocator, expression, SourceType::cjs());
-        let parsed = parser.parse();
-        if parsed.errors.len() > 0 {
-            let errors = parsed
-                .errors
-                .iter()
-                .map(|d| d.message.to_string())
-                .collect::<String>();
-            bail!("Parsing error: {:?}", errors)
-        }
-        let program = parsed.program;
-        let stmt = &program.body.first();
-        match stmt {
-            Some(Statement::ExpressionStatement(expr)) => self.evaluate_expr(&expr.expression),
-            Some(stmt) => bail!("Unsupported statement: {:?}", stmt),
-            None => {
-                let directive = &program.directives.first();
-                if let Some(directive) = directive {
-                    return Ok(Value::String(directive.directive.into_string()));
-                }
-                bail!("No statements found")
-            }
-        }
-    }
-
-    fn evaluate_expr(&self, expr: &Expression) -> Result<Value> {
-        match expr {
-            Expression::BooleanLiteral(expr) => Ok(Value::Bool(expr.value)),
-            Expression::NullLiteral(_) => Ok(Value::Null),
-            Expression::NumericLiteral(expr) => Ok(Value::Number(number_from_f64(expr.value)?)),
-            // Expression::BigIntLiteral()
-            // Expression::RegExpLiteral()
-            Expression::StringLiteral(expr) => Ok(Value::String(expr.value.into_string())),
-            // Expression::TemplateLiteral(expr) => Ok(Value::String(expr.quasis)),
-            Expression::Identifier(expr) => self.evaluate_by_name(&expr.name),
-            // Expression::MetaProperty()
-            // Expression::Super()
-            Expression::ArrayExpression(expr) => self.evaluate_array(expr),
-            // Expression::ArrowFunctionExpression()
-            // Expression::AssignmentExpression()
-            // Expression::AwaitExpression()
-            Expression::BinaryExpression(expr) => self.evaluate_binary(expr),
-            Expression::CallExpression(expr) => self.evaluate_call(expr),
-            Expression::ChainExpression(expr) => self.evaluate_chain(expr),
-            // Expression::ClassExpression()
-            Expression::ConditionalExpression(expr) => self.evaluate_conditional(expr),
-            // Expression::FunctionExpression()
-            // Expression::ImportExpression()
-            Expression::LogicalExpression(expr) => self.evaluate_logical(expr),
-            // Expression::NewExpression()
-            Expression::ObjectExpression(expr) => self.evaluate_object(expr),
-            Expression::ParenthesizedExpression(expr) => self.evaluate_expr(&expr.expression),
-            // Expression::SequenceExpression()
-            Expression::StaticMemberExpression(expr) => self.evaluate_static_member(expr),
-            // Expression::TaggedTemplateExpression()
-            // Expression::ThisExpression()
-            Expression::UnaryExpression(expr) => self.evaluate_unary(expr),
-            // Expression::UpdateExpression()
-            // Expression::YieldExpression()
-            // Expression::PrivateInExpression()
-            _ => bail!("Unsupported expression: {:?}", expr),
-        }
-    }
-
-    fn evaluate_array(&self, expr: &ArrayExpression) -> Result<Value> {
-        let result = expr
-            .elements
+        let compiled = Self::compile(expression)?;
+        self.evaluate_compiled(&compiled)
+    }
+
+    /// Like [`Evaluator::evaluate`], but reports failures as [`Diagnostic`]s
+    /// located in `expression`, one for every parse error.
+    pub fn diagnose(&self, expression: &str) -> std::result::Result<Value, Vec<Diagnostic>> {
+        self.evaluate(expression)
+            .map_err(|e| e.diagnostics(expression))
+    }
+
+    /// Evaluates `expression` with the entries of `overlay` shadowing the
+    /// evaluator's own context.
+    ///
+    /// The base context is not copied, so a long-lived evaluator can hold the
+    /// shared functions and constants while each call supplies a few
+    /// request-specific variables, e.g. a `HashMap<String, Value>`.
+    pub fn evaluate_with(&self, expression: &str, overlay: &dyn ContextProvider) -> Result<Value> {
+        let compiled = Self::compile(expression)?;
+        self.evaluate_compiled_with(&compiled, overlay)
+    }
+
+    /// Evaluates an expression previously returned by [`Evaluator::compile`].
+    pub fn evaluate_compiled(&self, expression: &CompiledExpression) -> Result<Value> {
+        let context = ContextChain::new(&self.context);
+        let value = self.evaluate_expr(&expression.expr, &Scope::new(&context))?;
+        self.output(value, expression.expr.span)
+    }
+
+    /// Evaluates a compiled expression with `overlay` shadowing the evaluator's
+    /// context. See [`Evaluator::evaluate_with`].
+    pub fn evaluate_compiled_with(
+        &self,
+        expression: &CompiledExpression,
+        overlay: &dyn ContextProvider,
+    ) -> Result<Value> {
+        let base = ContextChain::new(&self.context);
+        let context = base.child(overlay);
+        let value = self.evaluate_expr(&expression.expr, &Scope::new(&context))?;
+        self.output(value, expression.expr.span)
+    }
+
+    /// Evaluates `expression` in a mutable scope whose variables start as
+    /// `variables`, so assignments such as `x = 1`, `total += price` or `i++`
+    /// can update them.
+    ///
+    /// Variables shadow the context. Assigning to a name that isn't a variable
+    /// yet creates one, even if the context has an entry with that name; the
+    /// context itself is never changed. Properties can be assigned too, as in
+    /// `order.count += 1`, and the change shows in the returned variables when
+    /// the object is one of them.
+    ///
+    /// In the other evaluation modes only names bound inside the expression,
+    /// such as arrow function parameters, can be assigned to.
+    pub fn evaluate_mut(
+        &self,
+        expression: &str,
+        variables: HashMap<String, Value>,
+    ) -> Result<Evaluation> {
+        let compiled = Self::compile(expression)?;
+        self.evaluate_compiled_mut(&compiled, variables)
+    }
+
+    /// Evaluates a compiled expression in a mutable scope. See
+    /// [`Evaluator::evaluate_mut`].
+    pub fn evaluate_compiled_mut(
+        &self,
+        expression: &CompiledExpression,
+        variables: HashMap<String, Value>,
+    ) -> Result<Evaluation> {
+        let context = ContextChain::new(&self.context);
+        let variables = variables
             .iter()
-            .map(|f| self.evaluate_expr(f.to_expression()).unwrap())
-            .collect::<Vec<Value>>();
-        Ok(Value::Array(result))
+            .map(|(name, value)| (name.clone(), JsValue::from_json(value)))
+            .collect();
+        let scope = Scope::with_variables(&context, variables);
+        let value = self.evaluate_expr(&expression.expr, &scope)?;
+        let variables = scope
+            .variables()
+            .into_iter()
+            .map(|(name, value)| Ok((name, self.output(value, expression.expr.span)?)))
+            .collect::<Result<HashMap<String, Value>>>()?;
+        Ok(Evaluation {
+            value: self.output(value, expression.expr.span)?,
+            variables,
+        })
+    }
+
+    /// Parses `script` once so it can be run repeatedly with
+    /// [`Evaluator::evaluate_compiled_script`].
+    pub fn compile_script(script: &str) -> Result<CompiledScript> {
+        CompiledScript::parse(script)
+    }
+
+    /// Runs a script: a sequence of statements rather than a single
+    /// expression.
+    ///
+    /// Scripts can declare variables with `let` and `const`, which are scoped
+    /// to their block, and use `if`/`else`, `for...of` loops over arrays and
+    /// strings, and `return`. The result is the returned value or, without a
+    /// `return`, the value of the last expression statement that ran, as in
+    /// `eval`. A loop iterates over the elements the array had when it
+    /// started, so every loop ends.
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use oxide_eval::Evaluator;
+    ///
+    /// let evaluator = Evaluator::new(HashMap::new());
+    /// let script = "
+    ///     let total = 0;
+    ///     for (const price of [10, 20, 30]) {
+    ///         if (price > 15) total += price;
+    ///     }
+    ///     total;
+    /// ";
+    /// assert_eq!(evaluator.evaluate_script(script).unwrap(), 50);
+    /// ```
+    pub fn evaluate_script(&self, script: &str) -> Result<Value> {
+        let compiled = Self::compile_script(script)?;
+        self.evaluate_compiled_script(&compiled)
+    }
+
+    /// Runs a script previously returned by [`Evaluator::compile_script`].
+    pub fn evaluate_compiled_script(&self, script: &CompiledScript) -> Result<Value> {
+        let context = ContextChain::new(&self.context);
+        let scope = Scope::new(&context);
+        let scope = scope.child(None, HashMap::new());
+        let mut last = JsValue::Undefined;
+        let value = match self.execute_block(&script.statements, &scope, &mut last)? {
+            Completion::Return(value) => value,
+            Completion::Normal => last,
+        };
+        self.output(value, script.span)
+    }
+
+    fn output(&self, value: JsValue, span: Span) -> Result<Value> {
+        value
+            .to_json(self.output_policy)
+            .map_err(|e| e.with_span(span))
+    }
+
+    /// Runs `statements` in order until one of them returns. `last` is set to
+    /// the value of every expression statement.
+    fn execute_block(
+        &self,
+        statements: &[Stmt],
+        scope: &Scope,
+        last: &mut JsValue,
+    ) -> Result<Completion> {
+        for stmt in statements {
+            if let Completion::Return(value) = self.execute_statement(stmt, scope, last)? {
+                return Ok(Completion::Return(value));
+            }
+        }
+        Ok(Completion::Normal)
+    }
+
+    fn execute_statement(
+        &self,
+        stmt: &Stmt,
+        scope: &Scope,
+        last: &mut JsValue,
+    ) -> Result<Completion> {
+        let result = match &stmt.kind {
+            StmtKind::Expression(expr) => {
+                *last = self.evaluate_expr(expr, scope)?;
+                Ok(Completion::Normal)
+            }
+            StmtKind::Declaration {
+                constant,
+                declarators,
+            } => {
+                for (name, init) in declarators {
+                    let value = match init {
+                        Some(init) => self.evaluate_expr(init, scope)?,
+                        None => JsValue::Undefined,
+                    };
+                    scope.declare(name, value, *constant);
+                }
+                Ok(Completion::Normal)
+            }
+            StmtKind::Block(statements) => {
+                let scope = scope.child(scope.bindings(), HashMap::new());
+                self.execute_block(statements, &scope, last)
+            }
+            StmtKind::If {
+                test,
+                consequent,
+                alternate,
+            } => {
+                if value_to_bool(&self.evaluate_expr(test, scope)?) {
+                    self.execute_statement(consequent, scope, last)
+                } else if let Some(alternate) = alternate {
+                    self.execute_statement(alternate, scope, last)
+                } else {
+                    Ok(Completion::Normal)
+                }
+            }
+            StmtKind::ForOf {
+                constant,
+                binding,
+                iterable,
+                body,
+            } => self.execute_for_of(*constant, binding, iterable, body, scope, last),
+            StmtKind::Return(argument) => {
+                let value = match argument {
+                    Some(argument) => self.evaluate_expr(argument, scope)?,
+                    None => JsValue::Undefined,
+                };
+                Ok(Completion::Return(value))
+            }
+            StmtKind::Empty => Ok(Completion::Normal),
+            StmtKind::Unsupported(message) => Err(EvaluatorError::unsupported(message)),
+        };
+        result.map_err(|e| e.with_span(stmt.span))
+    }
+
+    fn execute_for_of(
+        &self,
+        constant: bool,
+        binding: &str,
+        iterable: &Expr,
+        body: &Stmt,
+        scope: &Scope,
+        last: &mut JsValue,
+    ) -> Result<Completion> {
+        let value = self.evaluate_expr(iterable, scope)?;
+        let values = iterate(&value).map_err(|e| e.with_span(iterable.span))?;
+        for value in values {
+            // Each iteration has its own binding, so function values created
+            // in the body see the element they were created for.
+            let scope = scope.child(scope.bindings(), HashMap::new());
+            scope.declare(binding, value, constant);
+            if let Completion::Return(value) = self.execute_statement(body, &scope, last)? {
+                return Ok(Completion::Return(value));
+            }
+        }
+        Ok(Completion::Normal)
+    }
+
+    fn evaluate_expr(&self, expr: &Expr, scope: &Scope) -> Result<JsValue> {
+        let result = match &expr.kind {
+            ExprKind::Literal(literal) => Ok(match literal {
+                Literal::Null => JsValue::Null,
+                Literal::Boolean(value) => JsValue::Bool(*value),
+                Literal::Number(value) => JsValue::Number(*value),
+                Literal::String(value) => JsValue::String(value.clone()),
+                Literal::RegExp(regexp) => JsValue::RegExp(Rc::new(regexp.clone())),
+            }),
+            ExprKind::Identifier(name) => self.evaluate_by_name(name, scope),
+            ExprKind::Array(elements) => self.evaluate_array(elements, scope),
+            ExprKind::Function(function) => Ok(JsValue::Function(Rc::new(Closure {
+                function: function.clone(),
+                bindings: scope.capture(),
+            }))),
+            ExprKind::Assignment {
+                operator,
+                target,
+                value,
+            } => self.evaluate_assignment(*operator, target, value, scope),
+            ExprKind::Binary {
+                operator,
+                left,
+                right,
+            } => self.evaluate_binary(*operator, left, right, scope),
+            ExprKind::Call {
+                callee,
+                callee_name,
+                arguments,
+            } => self
+                .evaluate_call(callee, callee_name.as_deref(), arguments, scope)
+                .map(Option::unwrap_or_default),
+            ExprKind::Chain(expr) => self
+                .evaluate_member(expr, scope)
+                .map(Option::unwrap_or_default),
+            ExprKind::Conditional {
+                test,
+                consequent,
+                alternate,
+            } => self.evaluate_conditional(test, consequent, alternate, scope),
+            ExprKind::Logical {
+                operator,
+                left,
+                right,
+            } => self.evaluate_logical(*operator, left, right, scope),
+            ExprKind::Object(properties) => self.evaluate_object(properties, scope),
+            ExprKind::Sequence(expressions) => self.evaluate_sequence(expressions, scope),
+            ExprKind::StaticMember { .. } | ExprKind::ComputedMember { .. } => self
+                .evaluate_member(expr, scope)
+                .map(Option::unwrap_or_default),
+            ExprKind::Template(template) => self.evaluate_template(template, scope),
+            ExprKind::TaggedTemplate { tag, template } => {
+                self.evaluate_tagged_template(tag, template, scope)
+            }
+            ExprKind::Unary { operator, argument } => {
+                self.evaluate_unary(*operator, argument, scope)
+            }
+            ExprKind::Update {
+                operator,
+                prefix,
+                target,
+            } => self.evaluate_update(*operator, *prefix, target, scope),
+            ExprKind::Unsupported(message) => Err(EvaluatorError::unsupported(message)),
+        };
+        result.map_err(|e| e.with_span(expr.span))
     }
-    fn evaluate_binary(&self, expr: &BinaryExpression) -> Result<Value> {
-        let left = self.evaluate_expr(&expr.left)?;
-        let right = self.evaluate_expr(&expr.right)?;
 
+    fn evaluate_array(&self, elements: &[ArrayElement], scope: &Scope) -> Result<JsValue> {
+        let mut result = Vec::with_capacity(elements.len());
+        for element in elements {
+            match element {
+                ArrayElement::Expr(expr) => result.push(self.evaluate_expr(expr, scope)?),
+                ArrayElement::Spread(expr) => {
+                    let value = self.evaluate_expr(expr, scope)?;
+                    result.extend(iterate(&value).map_err(|e| e.with_span(expr.span))?);
+                }
+                ArrayElement::Hole => result.push(JsValue::Undefined),
+            }
+        }
+        Ok(JsValue::array(result))
+    }
+    /// Evaluates an assignment. A compound assignment reads the target once;
+    /// the logical ones, `&&=`, `||=` and `??=`, only evaluate the value and
+    /// assign it when the current value doesn't decide the result.
+    fn evaluate_assignment(
+        &self,
+        operator: AssignmentOperator,
+        target: &AssignmentTarget,
+        value: &Expr,
+        scope: &Scope,
+    ) -> Result<JsValue> {
+        let reference = self.evaluate_reference(target, scope)?;
+        let value = if operator == AssignmentOperator::Assign {
+            self.evaluate_expr(value, scope)?
+        } else {
+            let current = self.read_reference(&reference, scope)?;
+            match (
+                operator.to_logical_operator(),
+                operator.to_binary_operator(),
+            ) {
+                (Some(logical), _) => {
+                    if short_circuits(logical, &current) {
+                        return Ok(current);
+                    }
+                    self.evaluate_expr(value, scope)?
+                }
+                (None, Some(binary)) => {
+                    let value = self.evaluate_expr(value, scope)?;
+                    self.binary_operation(binary, current, value)?
+                }
+                (None, None) => {
+                    return Err(EvaluatorError::unsupported(format!(
+                        "Unsupported assignment operator: {:?}",
+                        operator
+                    )))
+                }
+            }
+        };
+        self.write_reference(reference, value.clone(), scope)?;
+        Ok(value)
+    }
+    /// `++x`, `x--` and so on. The current value is converted to a number
+    /// first, so `x++` where `x` is `"1"` evaluates to `1` and sets `x` to `2`.
+    fn evaluate_update(
+        &self,
+        operator: UpdateOperator,
+        prefix: bool,
+        target: &AssignmentTarget,
+        scope: &Scope,
+    ) -> Result<JsValue> {
+        let reference = self.evaluate_reference(target, scope)?;
+        let old = value_to_number(&self.read_reference(&reference, scope)?);
+        let new = match operator {
+            UpdateOperator::Increment => old + 1.0,
+            UpdateOperator::Decrement => old - 1.0,
+        };
+        self.write_reference(reference, JsValue::Number(new), scope)?;
+        Ok(JsValue::Number(if prefix { new } else { old }))
+    }
+    /// Evaluates the object and key of a member target, before the value to
+    /// assign, as JavaScript does.
+    fn evaluate_reference(&self, target: &AssignmentTarget, scope: &Scope) -> Result<Reference> {
+        Ok(match target {
+            AssignmentTarget::Identifier(name) => Reference::Variable(name.clone()),
+            AssignmentTarget::StaticMember { object, property } => Reference::Property {
+                object: self.evaluate_expr(object, scope)?,
+                key: JsValue::String(property.clone()),
+            },
+            AssignmentTarget::ComputedMember { object, property } => Reference::Property {
+                object: self.evaluate_expr(object, scope)?,
+                key: self.evaluate_expr(property, scope)?,
+            },
+        })
+    }
+    /// Reads the current value of a compound assignment or update target. A
+    /// missing property is `undefined`, so `counts.a ??= 0` works.
+    fn read_reference(&self, reference: &Reference, scope: &Scope) -> Result<JsValue> {
+        match reference {
+            Reference::Variable(name) => self.evaluate_by_name(name, scope),
+            Reference::Property { object, key } => self.get_computed(object, key, true),
+        }
+    }
+    fn write_reference(&self, reference: Reference, value: JsValue, scope: &Scope) -> Result<()> {
+        match reference {
+            Reference::Variable(name) => scope.assign(&name, value),
+            Reference::Property { object, key } => {
+                scope.stored(&object, &value);
+                set_property(&object, &key, value)
+            }
+        }
+    }
+    fn evaluate_binary(
+        &self,
+        operator: BinaryOperator,
+        left: &Expr,
+        right: &Expr,
+        scope: &Scope,
+    ) -> Result<JsValue> {
+        if operator == BinaryOperator::Instanceof {
+            return self.evaluate_instanceof(left, right, scope);
+        }
+        let left = self.evaluate_expr(left, scope)?;
+        let right = self.evaluate_expr(right, scope)?;
+        self.binary_operation(operator, left, right)
+    }
+    /// Applies a binary operator to evaluated operands.
+    fn binary_operation(
+        &self,
+        operator: BinaryOperator,
+        left: JsValue,
+        right: JsValue,
+    ) -> Result<JsValue> {
         #[cfg(feature = "semver-support")]
         {
             use semver_wrapper::SemverWrapper;
-            use serde::Deserialize;
-            if let (Ok(left), Ok(right)) = (
-                SemverWrapper::deserialize(&left),
-                SemverWrapper::deserialize(&right),
+            if let (Some(left), Some(right)) = (
+                SemverWrapper::from_object(&left),
+                SemverWrapper::from_object(&right),
             ) {
                 let cmp = left.version.cmp_precedence(&right.version);
-                let result = match expr.operator {
+                let result = match operator {
                     BinaryOperator::Equality | BinaryOperator::StrictEquality => cmp.is_eq(),
                     BinaryOperator::Inequality | BinaryOperator::StrictInequality => !cmp.is_eq(),
                     BinaryOperator::LessThan => cmp.is_lt(),
                     BinaryOperator::LessEqualThan => cmp.is_le(),
                     BinaryOperator::GreaterThan => cmp.is_gt(),
                     BinaryOperator::GreaterEqualThan => cmp.is_gt(),
-                    _ => bail!(
-                        "Unsupported binary operator for semver: {:?}",
-                        expr.operator
-                    ),
+                    _ => {
+                        return Err(EvaluatorError::unsupported(format!(
+                            "Unsupported binary operator for semver: {:?}",
+                            operator
+                        )))
+                    }
                 };
-                return Ok(Value::Bool(result));
+                return Ok(JsValue::Bool(result));
             }
         }
 
-        match expr.operator {
-            BinaryOperator::Equality => Ok(Value::Bool(equality(&left, &right, false))),
-            BinaryOperator::Inequality => Ok(Value::Bool(!equality(&left, &right, false))),
-            BinaryOperator::StrictEquality => Ok(Value::Bool(equality(&left, &right, true))),
-            BinaryOperator::StrictInequality => Ok(Value::Bool(!equality(&left, &right, true))),
-            BinaryOperator::LessThan => Ok(Value::Bool(compare(&left, &right, |l, r| l < r))),
-            BinaryOperator::LessEqualThan => Ok(Value::Bool(compare(&left, &right, |l, r| l <= r))),
-            BinaryOperator::GreaterThan => Ok(Value::Bool(compare(&left, &right, |l, r| l > r))),
+        let result = match operator {
+            BinaryOperator::Equality => JsValue::Bool(equality(&left, &right, false)),
+            BinaryOperator::Inequality => JsValue::Bool(!equality(&left, &right, false)),
+            BinaryOperator::StrictEquality => JsValue::Bool(equality(&left, &right, true)),
+            BinaryOperator::StrictInequality => JsValue::Bool(!equality(&left, &right, true)),
+            BinaryOperator::LessThan => {
+                JsValue::Bool(compare(&left, &right).is_some_and(Ordering::is_lt))
+            }
+            BinaryOperator::LessEqualThan => {
+                JsValue::Bool(compare(&left, &right).is_some_and(Ordering::is_le))
+            }
+            BinaryOperator::GreaterThan => {
+                JsValue::Bool(compare(&left, &right).is_some_and(Ordering::is_gt))
+            }
             BinaryOperator::GreaterEqualThan => {
-                Ok(Value::Bool(compare(&left, &right, |l, r| l >= r)))
+                JsValue::Bool(compare(&left, &right).is_some_and(Ordering::is_ge))
             }
             BinaryOperator::Addition => addition(left, right),
             BinaryOperator::Subtraction => subtraction(left, right),
@@ -169,263 +607,723 @@ impl Evaluator {
             BinaryOperator::BitwiseOR => bitwise_operation(left, right, |l, r| l | r),
             BinaryOperator::BitwiseXOR => bitwise_operation(left, right, |l, r| l ^ r),
             BinaryOperator::BitwiseAnd => bitwise_operation(left, right, |l, r| l & r),
-            _ => bail!("Unsupported binary operator: {:?}", expr.operator),
+            BinaryOperator::In => JsValue::Bool(has_property(&left, &right)?),
+            _ => {
+                return Err(EvaluatorError::unsupported(format!(
+                    "Unsupported binary operator: {:?}",
+                    operator
+                )))
+            }
+        };
+        Ok(result)
+    }
+    /// `instanceof` with one of the built-in constructors, such as
+    /// `value instanceof Array`, unless a variable shadows its name. Function
+    /// values can't construct objects, so nothing is an instance of them.
+    fn evaluate_instanceof(&self, left: &Expr, right: &Expr, scope: &Scope) -> Result<JsValue> {
+        let value = self.evaluate_expr(left, scope)?;
+        if let ExprKind::Identifier(name) = &right.kind {
+            if scope.variable(name).is_none() {
+                if let Some(result) = instance_of(&value, name) {
+                    return Ok(JsValue::Bool(result));
+                }
+            }
+        }
+        match self.evaluate_expr(right, scope)? {
+            JsValue::Function(_) => Ok(JsValue::Bool(false)),
+            constructor => Err(EvaluatorError::type_error(format!(
+                "Right-hand side of 'instanceof' is not callable: {}",
+                value_to_string(&constructor)
+            ))),
         }
     }
-    fn evaluate_call(&self, expr: &CallExpression) -> Result<Value> {
-        let args = expr
-            .arguments
-            .iter()
-            .map(|f| self.evaluate_expr(f.to_expression()).unwrap())
-            .collect::<Vec<Value>>();
-        match &expr.callee {
-            Expression::Identifier(expr) => {
-                let callee_name = expr.name.to_string();
-
-                #[cfg(feature = "semver-support")]
-                {
-                    use semver_wrapper::SemverWrapper;
-                    if callee_name == "semver" {
-                        return Ok(serde_json::json!(SemverWrapper::from_values(args)?));
-                    }
+    /// Calls a host function, built-in method or function value. Returns
+    /// `None` when an optional chain short-circuits before the call.
+    fn evaluate_call(
+        &self,
+        callee: &Expr,
+        callee_name: Option<&str>,
+        arguments: &[Expr],
+        scope: &Scope,
+    ) -> Result<Option<JsValue>> {
+        // The callee and its receiver are evaluated before the arguments, so
+        // a short-circuited optional call doesn't evaluate them at all.
+        match &callee.kind {
+            ExprKind::Identifier(name) => self.call_by_name(name, arguments, scope).map(Some),
+            // Built-in methods are looked up by name on the receiver, e.g. the
+            // string in `name.toUpperCase()`.
+            ExprKind::StaticMember {
+                object, optional, ..
+            } => {
+                let Some(callee) = self.evaluate_member_object(object, *optional, scope)? else {
+                    return Ok(None);
+                };
+                let args = self.evaluate_arguments(arguments, scope)?;
+                self.call_method(&callee, callee_name, args, scope)
+                    .map(Some)
+            }
+            // Any other callee, such as `handlers[0]` or `(x => x * 2)`, must
+            // evaluate to a function value.
+            _ => {
+                let Some(function) = self.evaluate_member(callee, scope)? else {
+                    return Ok(None);
+                };
+                let args = self.evaluate_arguments(arguments, scope)?;
+                match function {
+                    JsValue::Function(_) => self.call_function(&function, args, scope).map(Some),
+                    _ => Err(EvaluatorError::unknown_function(
+                        callee_name.unwrap_or_default(),
+                    )),
                 }
+            }
+        }
+    }
+    fn call_by_name(&self, name: &str, arguments: &[Expr], scope: &Scope) -> Result<JsValue> {
+        // Function values shadow host functions of the same name.
+        if let Some(function @ JsValue::Function(_)) = scope.variable(name) {
+            let args = self.evaluate_arguments(arguments, scope)?;
+            return self.call_function(&function, args, scope);
+        }
+        let args = self.evaluate_arguments(arguments, scope)?;
+        let callee_name = name.to_string();
 
-                match self.context.get(&callee_name) {
-                    Some(ContextEntry::Function(f)) => Ok(f(args)),
-                    _ => {
-                        #[cfg(feature = "string")]
-                        {
-                            if let Some(Value::String(callee)) = args.get(0) {
-                                return match Evaluator::evaluate_str_method(
-                                    callee,
-                                    &callee_name,
-                                    args[1..].to_vec(),
-                                ) {
-                                    Ok(value) => Ok(value),
-                                    _ => {
-                                        #[cfg(feature = "math")]
-                                        {
-                                            if let Ok(result) = Evaluator::evaluate_math_function(
-                                                &callee_name,
-                                                args,
-                                            ) {
-                                                return Ok(result);
-                                            }
-                                        }
-                                        bail!("{:?} not found in function context", callee_name)
-                                    }
-                                };
-                            }
-                        }
-                        #[cfg(feature = "array")]
-                        {
-                            if let Some(Value::Array(callee)) = args.get(0) {
-                                return match Evaluator::evaluate_array_method(
-                                    callee,
-                                    &callee_name,
-                                    args[1..].to_vec(),
-                                ) {
-                                    Ok(value) => Ok(value),
-                                    _ => bail!("{:?} not found in function context", callee_name),
-                                };
-                            }
-                        }
-                        #[cfg(feature = "math")]
-                        {
-                            if let Some(Value::Number(_)) = args.get(0) {
-                                return Evaluator::evaluate_math_function(&callee_name, args);
+        #[cfg(feature = "semver-support")]
+        {
+            use semver_wrapper::SemverWrapper;
+            if callee_name == "semver" {
+                let args = self.host_arguments(args)?;
+                let version = serde_json::json!(SemverWrapper::from_values(args)?);
+                return Ok(JsValue::from_json(&version));
+            }
+        }
+
+        match scope.function(&callee_name) {
+            Some(ContextEntry::Function(f)) => {
+                Ok(JsValue::from_json(&f(self.host_arguments(args)?)))
+            }
+            Some(ContextEntry::FallibleFunction(f)) => f(self.host_arguments(args)?)
+                .map(|value| JsValue::from_json(&value))
+                .map_err(|source| EvaluatorError::HostFunction {
+                    name: callee_name,
+                    source,
+                    span: Span::default(),
+                }),
+            _ => {
+                #[cfg(feature = "string")]
+                {
+                    if let Some(JsValue::String(callee)) = args.first() {
+                        return match Evaluator::evaluate_str_method(
+                            callee,
+                            &callee_name,
+                            args[1..].to_vec(),
+                        ) {
+                            Err(EvaluatorError::UnknownFunction { .. }) => {
+                                #[cfg(feature = "math")]
+                                {
+                                    Evaluator::evaluate_math_function(&callee_name, args)
+                                }
+                                #[cfg(not(feature = "math"))]
+                                Err(EvaluatorError::unknown_function(callee_name))
                             }
-                        }
-                        bail!("{:?} not found in function context", callee_name)
+                            result => result,
+                        };
                     }
                 }
-            }
-            _ => {
-                let callee = self.evaluate_expr(&expr.callee)?;
-
-                if let Value::String(callee) = &callee {
-                    #[cfg(feature = "string")]
-                    {
-                        let callee_name = expr.callee_name().unwrap_or_default();
-                        return Evaluator::evaluate_str_method(callee, callee_name, args);
+                #[cfg(feature = "array")]
+                {
+                    if let Some(JsValue::Array(callee)) = args.first() {
+                        return self.evaluate_array_method(
+                            callee,
+                            &callee_name,
+                            args[1..].to_vec(),
+                            scope,
+                        );
                     }
-                    #[cfg(not(feature = "string"))]
-                    bail!("'string' feature is not enabled. callee: {:?}", callee)
-                } else if let Value::Array(callee) = &callee {
-                    #[cfg(feature = "array")]
-                    {
-                        let callee_name = expr.callee_name().unwrap_or_default();
-                        return Evaluator::evaluate_array_method(callee, callee_name, args);
+                }
+                #[cfg(feature = "math")]
+                {
+                    if let Some(JsValue::Number(_)) = args.first() {
+                        return Evaluator::evaluate_math_function(&callee_name, args);
                     }
-                    #[cfg(not(feature = "array"))]
-                    bail!("'array' feature is not enabled. callee: {:?}", callee)
                 }
-
-                bail!("Unsupported method for {:?}", callee);
+                Err(EvaluatorError::unknown_function(callee_name))
             }
         }
     }
-    fn evaluate_chain(&self, expr: &ChainExpression) -> Result<Value> {
-        let ex = &expr.expression;
-        match ex {
-            ChainElement::CallExpression(expr) => self.evaluate_call(expr),
-            ChainElement::StaticMemberExpression(expr) => self.evaluate_static_member(expr),
-            _ => bail!("Unsupported ChainExpression: {:?}", ex),
+    #[cfg_attr(
+        not(any(feature = "string", feature = "array")),
+        allow(unused_variables)
+    )]
+    fn call_method(
+        &self,
+        callee: &JsValue,
+        callee_name: Option<&str>,
+        args: Vec<JsValue>,
+        scope: &Scope,
+    ) -> Result<JsValue> {
+        if let JsValue::String(callee) = callee {
+            #[cfg(feature = "string")]
+            {
+                let callee_name = callee_name.unwrap_or_default();
+                return Evaluator::evaluate_str_method(callee, callee_name, args);
+            }
+            #[cfg(not(feature = "string"))]
+            return Err(EvaluatorError::unsupported(format!(
+                "'string' feature is not enabled. callee: {:?}",
+                callee
+            )));
+        } else if let JsValue::Array(callee) = callee {
+            #[cfg(feature = "array")]
+            {
+                let callee_name = callee_name.unwrap_or_default();
+                return self.evaluate_array_method(callee, callee_name, args, scope);
+            }
+            #[cfg(not(feature = "array"))]
+            return Err(EvaluatorError::unsupported(format!(
+                "'array' feature is not enabled. callee: {:?}",
+                callee
+            )));
+        } else if let JsValue::RegExp(callee) = callee {
+            #[cfg(feature = "string")]
+            {
+                let callee_name = callee_name.unwrap_or_default();
+                return Evaluator::evaluate_regexp_method(callee, callee_name, args);
+            }
+            #[cfg(not(feature = "string"))]
+            return Err(EvaluatorError::unsupported(format!(
+                "'string' feature is not enabled. callee: {}",
+                callee
+            )));
+        } else if let JsValue::Object(_) = callee {
+            let callee_name = callee_name.unwrap_or_default();
+            let method = self.get_property(callee, callee_name, false)?;
+            if let JsValue::Function(_) = method {
+                return self.call_function(&method, args, scope);
+            }
         }
+
+        Err(EvaluatorError::unknown_function(
+            callee_name.unwrap_or_default(),
+        ))
+    }
+    fn evaluate_arguments(&self, arguments: &[Expr], scope: &Scope) -> Result<Vec<JsValue>> {
+        arguments
+            .iter()
+            .map(|argument| self.evaluate_expr(argument, scope))
+            .collect()
     }
-    fn evaluate_conditional(&self, expr: &ConditionalExpression) -> Result<Value> {
-        let test = self.evaluate_value(&self.evaluate_expr(&expr.test)?);
+    fn evaluate_conditional(
+        &self,
+        test: &Expr,
+        consequent: &Expr,
+        alternate: &Expr,
+        scope: &Scope,
+    ) -> Result<JsValue> {
+        let test = value_to_bool(&self.evaluate_expr(test, scope)?);
         let expr = match test {
-            true => &expr.consequent,
-            false => &expr.alternate,
+            true => consequent,
+            false => alternate,
         };
-        self.evaluate_expr(expr)
+        self.evaluate_expr(expr, scope)
     }
-    fn evaluate_logical(&self, expr: &LogicalExpression) -> Result<Value> {
-        let operator = expr.operator;
-        let left = &self.evaluate_expr(&expr.left)?;
-        let right = &self.evaluate_expr(&expr.right)?;
-        match operator {
-            LogicalOperator::And => Ok(Value::Bool(
-                self.evaluate_value(left) && self.evaluate_value(right),
-            )),
-            LogicalOperator::Coalesce => match left {
-                Value::Null => Ok(right.clone()),
-                _ => Ok(left.clone()),
-            },
-            LogicalOperator::Or => match self.evaluate_value(left) {
-                true => Ok(left.clone()),
-                false => Ok(right.clone()),
-            },
+    fn evaluate_logical(
+        &self,
+        operator: LogicalOperator,
+        left: &Expr,
+        right: &Expr,
+        scope: &Scope,
+    ) -> Result<JsValue> {
+        // The right operand is only evaluated when the left one doesn't decide
+        // the result, so its errors and host function calls are skipped.
+        let left_value = self.evaluate_expr(left, scope)?;
+        if short_circuits(operator, &left_value) {
+            return Ok(left_value);
         }
+        self.evaluate_expr(right, scope)
     }
-    fn evaluate_object(&self, expr: &ObjectExpression) -> Result<Value> {
-        let properties = &expr.properties;
-        let mut map = Map::new();
+    /// Builds an object literal. Later properties overwrite earlier ones with
+    /// the same key, including those copied by a spread.
+    fn evaluate_object(&self, properties: &[Property], scope: &Scope) -> Result<JsValue> {
+        let mut map = BTreeMap::new();
         for property in properties {
             match property {
-                ObjectPropertyKind::ObjectProperty(object_property) => {
-                    let key = match object_property.key.as_expression() {
-                        Some(v) => v,
-                        _ => bail!("Object key is not an expression"),
+                Property::KeyValue { key, value } => {
+                    let key = match key {
+                        PropertyKey::Static(key) => key.clone(),
+                        PropertyKey::Computed(key) => {
+                            value_to_string(&self.evaluate_expr(key, scope)?)
+                        }
                     };
-                    let key = self.evaluate_expr(key)?;
-                    let key = to_string(&key)?;
-                    let value = self.evaluate_expr(&object_property.value)?;
+                    let value = self.evaluate_expr(value, scope)?;
                     map.insert(key, value);
                 }
-                _ => bail!("Unsupported ObjectPropertyKind: {:?}", property),
+                Property::Spread(expr) => {
+                    let value = self.evaluate_expr(expr, scope)?;
+                    map.extend(own_properties(&value));
+                }
             }
         }
-        Ok(Value::Object(map))
+        Ok(JsValue::object(map))
     }
-    fn evaluate_static_member(&self, expr: &StaticMemberExpression) -> Result<Value> {
-        let obj = if expr.optional {
-            match self.evaluate_expr(&expr.object) {
-                Ok(value) => value,
-                Err(e) => {
-                    if e.downcast_ref::<EvaluatorError>().map_or(false, |err| {
-                        matches!(err, EvaluatorError::VariableNotFound(_))
-                    }) {
-                        return Ok(Value::Null);
-                    }
-                    return Err(e);
-                }
+    /// Evaluates comma separated expressions strictly from left to right, so
+    /// host functions called for their side effects run in the order written,
+    /// and returns the value of the last one. An error stops the sequence.
+    fn evaluate_sequence(&self, expressions: &[Expr], scope: &Scope) -> Result<JsValue> {
+        let mut value = JsValue::Undefined;
+        for expr in expressions {
+            value = self.evaluate_expr(expr, scope)?;
+        }
+        Ok(value)
+    }
+    /// Evaluates a member access or call. Returns `None` when an optional access
+    /// short-circuits the rest of the chain, as in `user?.address.city`.
+    fn evaluate_member(&self, expr: &Expr, scope: &Scope) -> Result<Option<JsValue>> {
+        let result = match &expr.kind {
+            ExprKind::StaticMember {
+                object,
+                property,
+                optional,
+            } => self.evaluate_static_member(object, property, *optional, scope),
+            ExprKind::ComputedMember {
+                object,
+                property,
+                optional,
+            } => self.evaluate_computed_member(object, property, *optional, scope),
+            ExprKind::Call {
+                callee,
+                callee_name,
+                arguments,
+            } => self.evaluate_call(callee, callee_name.as_deref(), arguments, scope),
+            _ => self.evaluate_expr(expr, scope).map(Some),
+        };
+        result.map_err(|e| e.with_span(expr.span))
+    }
+    fn evaluate_static_member(
+        &self,
+        object: &Expr,
+        property: &str,
+        optional: bool,
+        scope: &Scope,
+    ) -> Result<Option<JsValue>> {
+        let Some(obj) = self.evaluate_member_object(object, optional, scope)? else {
+            return Ok(None);
+        };
+        self.get_property(&obj, property, optional).map(Some)
+    }
+    fn evaluate_computed_member(
+        &self,
+        object: &Expr,
+        property: &Expr,
+        optional: bool,
+        scope: &Scope,
+    ) -> Result<Option<JsValue>> {
+        let Some(obj) = self.evaluate_member_object(object, optional, scope)? else {
+            return Ok(None);
+        };
+        let key = self.evaluate_expr(property, scope)?;
+        self.get_computed(&obj, &key, optional).map(Some)
+    }
+    /// Reads `obj[key]`. Integer keys of arrays and strings may be negative to
+    /// count back from the end.
+    fn get_computed(&self, obj: &JsValue, key: &JsValue, optional: bool) -> Result<JsValue> {
+        let (length, value) = match (obj, value_to_index(key)) {
+            (JsValue::Array(arr), Some(index)) => {
+                let arr = arr.borrow();
+                let value = relative_index(index, arr.len()).map(|i| arr[i].clone());
+                (arr.len(), value)
             }
-        } else {
-            self.evaluate_expr(&expr.object)?
+            (JsValue::String(s), Some(index)) => {
+                let units = s.encode_utf16().collect::<Vec<u16>>();
+                let value = relative_index(index, units.len())
+                    .map(|i| JsValue::String(String::from_utf16_lossy(&units[i..=i])));
+                (units.len(), value)
+            }
+            _ => return self.get_property(obj, &value_to_string(key), optional),
         };
-        let property = expr.property.name.to_string();
-        match &obj {
-            Value::Object(map) => {
-                let value = map.get(&property);
-                if let Some(value) = value {
-                    return Ok(value.clone());
+        match value {
+            Some(value) => Ok(value),
+            None if optional => Ok(JsValue::Undefined),
+            None => Err(EvaluatorError::IndexOutOfRange {
+                index: value_to_index(key).unwrap_or_default(),
+                length,
+                span: Span::default(),
+            }),
+        }
+    }
+    /// Evaluates the object of a member expression. Returns `None` when an
+    /// optional access short-circuits because the object is nullish or is an
+    /// undeclared variable, as in `undeclared?.name`. A variable missing
+    /// anywhere else, as in `f(undeclared)?.name`, is still an error.
+    fn evaluate_member_object(
+        &self,
+        object: &Expr,
+        optional: bool,
+        scope: &Scope,
+    ) -> Result<Option<JsValue>> {
+        short_circuit_object(object, self.evaluate_member(object, scope), optional)
+    }
+    /// Reads a named property. Missing properties of objects and arrays are
+    /// errors unless `optional` is set; missing properties of other primitives
+    /// are `undefined`, or errors in strict mode.
+    fn get_property(&self, obj: &JsValue, property: &str, optional: bool) -> Result<JsValue> {
+        let value = match obj {
+            JsValue::Undefined | JsValue::Null => {
+                return Err(EvaluatorError::type_error(format!(
+                    "Cannot read properties of {} (reading '{}')",
+                    value_to_string(obj),
+                    property
+                )))
+            }
+            JsValue::Object(map) => map.borrow().get(property).cloned(),
+            JsValue::Array(arr) if property == "length" => {
+                Some(JsValue::Number(arr.borrow().len() as f64))
+            }
+            JsValue::Array(arr) => arr.borrow().properties.get(property).cloned(),
+            JsValue::RegExp(re) => re.property(property),
+            JsValue::String(s) if property == "length" => {
+                Some(JsValue::Number(s.encode_utf16().count() as f64))
+            }
+            JsValue::Function(_) => None,
+            JsValue::Bool(_) | JsValue::Number(_) | JsValue::String(_) if !self.strict => {
+                Some(JsValue::Undefined)
+            }
+            JsValue::Bool(_) | JsValue::Number(_) | JsValue::String(_) => None,
+        };
+        match value {
+            Some(value) => Ok(value),
+            None if optional => Ok(JsValue::Undefined),
+            None => Err(property_not_found(obj, property)),
+        }
+    }
+    /// Joins the parts of a template literal. Embedded values are converted
+    /// with `ToString`, like the operands of `+`.
+    fn evaluate_template(&self, template: &Template, scope: &Scope) -> Result<JsValue> {
+        let mut quasis = template
+            .quasis
+            .iter()
+            .map(|quasi| quasi.cooked.as_deref().unwrap_or_default());
+        let mut result = quasis.next().unwrap_or_default().to_string();
+        for (expr, quasi) in template.expressions.iter().zip(quasis) {
+            let value = self.evaluate_expr(expr, scope)?;
+            result.push_str(&value_to_string(&value));
+            result.push_str(quasi);
+        }
+        Ok(JsValue::String(result))
+    }
+    /// Calls the tag of a tagged template. A [`ContextEntry::TagFunction`]
+    /// receives the string parts and the values separately. Any other function
+    /// is called like in JavaScript, with the array of cooked strings followed
+    /// by the values; function values also see the raw strings as
+    /// `strings.raw`.
+    fn evaluate_tagged_template(
+        &self,
+        tag: &Expr,
+        template: &Template,
+        scope: &Scope,
+    ) -> Result<JsValue> {
+        // Like the arguments of a call, the values are evaluated after the tag.
+        let values = || self.evaluate_arguments(&template.expressions, scope);
+        let cooked = template
+            .quasis
+            .iter()
+            .map(|quasi| quasi.cooked.clone())
+            .collect::<Vec<Option<String>>>();
+        let raw = template
+            .quasis
+            .iter()
+            .map(|quasi| quasi.raw.clone())
+            .collect::<Vec<String>>();
+        let cooked_value = || {
+            let cooked = cooked.iter().map(|cooked| match cooked {
+                Some(cooked) => JsValue::from(cooked.as_str()),
+                None => JsValue::Undefined,
+            });
+            cooked.collect::<Vec<JsValue>>()
+        };
+
+        if let ExprKind::Identifier(name) = &tag.kind {
+            let host_error = |source| EvaluatorError::HostFunction {
+                name: name.clone(),
+                source,
+                span: Span::default(),
+            };
+            match scope.function(name) {
+                Some(ContextEntry::TagFunction(f)) => {
+                    let strings = TemplateStrings { cooked, raw };
+                    return f(strings, self.host_arguments(values()?)?)
+                        .map(|value| JsValue::from_json(&value))
+                        .map_err(host_error);
                 }
-                if expr.optional {
-                    return Ok(Value::Null);
+                Some(ContextEntry::Function(f)) => {
+                    let args = [vec![JsValue::array(cooked_value())], values()?].concat();
+                    return Ok(JsValue::from_json(&f(self.host_arguments(args)?)));
                 }
-                Err(anyhow!(EvaluatorError::PropertyNotFound(
-                    obj.to_string(),
-                    property.clone(),
-                )))
+                Some(ContextEntry::FallibleFunction(f)) => {
+                    let args = [vec![JsValue::array(cooked_value())], values()?].concat();
+                    return f(self.host_arguments(args)?)
+                        .map(|value| JsValue::from_json(&value))
+                        .map_err(host_error);
+                }
+                _ => {}
             }
-            _ => Ok(obj),
         }
+        let function = match self.evaluate_expr(tag, scope) {
+            Err(EvaluatorError::VariableNotFound { name, .. }) => {
+                return Err(EvaluatorError::unknown_function(name))
+            }
+            result => result?,
+        };
+        let raw = JsValue::array(raw.into_iter().map(JsValue::String).collect());
+        let strings =
+            JsValue::array_with_properties(cooked_value(), BTreeMap::from([("raw".into(), raw)]));
+        self.call_function(&function, [vec![strings], values()?].concat(), scope)
     }
-    fn evaluate_unary(&self, expr: &UnaryExpression) -> Result<Value> {
-        let operator = expr.operator;
-        let value = self.evaluate_expr(&expr.argument)?;
+    fn evaluate_unary(
+        &self,
+        operator: UnaryOperator,
+        argument: &Expr,
+        scope: &Scope,
+    ) -> Result<JsValue> {
+        if operator == UnaryOperator::Typeof {
+            return self.evaluate_typeof(argument, scope);
+        }
+        let value = self.evaluate_expr(argument, scope)?;
         match operator {
-            UnaryOperator::UnaryPlus => unary_plus(value),
-            UnaryOperator::UnaryNegation => unary_negation(value),
-            UnaryOperator::BitwiseNot => unary_bitwise_not(value),
-            UnaryOperator::LogicalNot => Ok(Value::Bool(!self.evaluate_value(&value))),
-            _ => {
-                bail!("Unsupported UnaryOperator {:?}", operator)
-            }
+            UnaryOperator::Void => Ok(JsValue::Undefined),
+            UnaryOperator::UnaryPlus => Ok(unary_plus(value)),
+            UnaryOperator::UnaryNegation => Ok(unary_negation(value)),
+            UnaryOperator::BitwiseNot => Ok(unary_bitwise_not(value)),
+            UnaryOperator::LogicalNot => Ok(JsValue::Bool(!value_to_bool(&value))),
+            _ => Err(EvaluatorError::unsupported(format!(
+                "Unsupported UnaryOperator {:?}",
+                operator
+            ))),
         }
     }
 
-    fn evaluate_by_name(&self, name: &str) -> Result<Value> {
-        match self.context.get(name) {
-            Some(ContextEntry::Variable(value)) => Ok(value.clone()),
-            _ => Err(anyhow!(EvaluatorError::VariableNotFound(name.to_string()))),
+    /// `typeof` of an identifier that isn't defined is `"undefined"` rather
+    /// than an error, and host functions are `"function"`. So is a missing
+    /// property or index, as in `typeof user.nickname`.
+    fn evaluate_typeof(&self, argument: &Expr, scope: &Scope) -> Result<JsValue> {
+        let result = match &argument.kind {
+            ExprKind::StaticMember { .. }
+            | ExprKind::ComputedMember { .. }
+            | ExprKind::Chain(_) => self
+                .evaluate_typeof_member(argument, scope)
+                .map(Option::unwrap_or_default),
+            _ => self.evaluate_expr(argument, scope),
+        };
+        let value = match (result, &argument.kind) {
+            (Err(EvaluatorError::VariableNotFound { .. }), ExprKind::Identifier(name)) => {
+                if scope.function(name).is_some() {
+                    return Ok(JsValue::from("function"));
+                }
+                JsValue::Undefined
+            }
+            (result, _) => result?,
+        };
+        Ok(JsValue::from(type_of(&value)))
+    }
+
+    /// Evaluates a member access like [`Evaluator::evaluate_member`], except
+    /// that missing properties and indexes along the way are `undefined`, as
+    /// in JavaScript. Reading from `undefined` or `null` still fails.
+    fn evaluate_typeof_member(&self, expr: &Expr, scope: &Scope) -> Result<Option<JsValue>> {
+        let result = match &expr.kind {
+            ExprKind::StaticMember {
+                object,
+                property,
+                optional,
+            } => {
+                let value = self.evaluate_typeof_member(object, scope);
+                let Some(obj) = short_circuit_object(object, value, *optional)? else {
+                    return Ok(None);
+                };
+                self.get_property(&obj, property, true).map(Some)
+            }
+            ExprKind::ComputedMember {
+                object,
+                property,
+                optional,
+            } => {
+                let value = self.evaluate_typeof_member(object, scope);
+                let Some(obj) = short_circuit_object(object, value, *optional)? else {
+                    return Ok(None);
+                };
+                let key = self.evaluate_expr(property, scope)?;
+                self.get_computed(&obj, &key, true).map(Some)
+            }
+            ExprKind::Chain(expr) => self
+                .evaluate_typeof_member(expr, scope)
+                .map(|value| Some(value.unwrap_or_default())),
+            _ => self.evaluate_member(expr, scope),
+        };
+        result.map_err(|e| e.with_span(expr.span))
+    }
+
+    fn evaluate_by_name(&self, name: &str, scope: &Scope) -> Result<JsValue> {
+        if let Some(value) = scope.variable(name) {
+            return Ok(value);
+        }
+        // Context entries may shadow these globals.
+        match name {
+            "undefined" => Ok(JsValue::Undefined),
+            "NaN" => Ok(JsValue::Number(f64::NAN)),
+            "Infinity" => Ok(JsValue::Number(f64::INFINITY)),
+            _ => Err(EvaluatorError::VariableNotFound {
+                name: name.to_string(),
+                span: Span::default(),
+            }),
         }
     }
-    fn evaluate_value(&self, value: &Value) -> bool {
-        match value {
-            Value::Array(arr) => !arr.is_empty(),
-            Value::Bool(bool) => *bool,
-            Value::Null => false,
-            Value::Number(number) => number.as_f64().is_some_and(|value| value != 0.0),
-            Value::Object(_) => true,
-            Value::String(str) => !str.is_empty(),
+    /// Calls a function value with `args` bound to its parameters in a child
+    /// scope of the bindings it was created in. Missing arguments are
+    /// `undefined` and extra ones are ignored. A named function expression
+    /// also sees itself under its name, so it can recurse.
+    fn call_function(
+        &self,
+        function: &JsValue,
+        args: Vec<JsValue>,
+        scope: &Scope,
+    ) -> Result<JsValue> {
+        let JsValue::Function(closure) = function else {
+            return Err(EvaluatorError::type_error(format!(
+                "{} is not a function",
+                value_to_string(function)
+            )));
+        };
+        if scope.depth() >= self.max_call_depth {
+            return Err(EvaluatorError::CallDepthExceeded {
+                limit: self.max_call_depth,
+                span: Span::default(),
+            });
+        }
+        let mut args = args.into_iter();
+        let mut values = closure
+            .function
+            .params
+            .iter()
+            .map(|param| (param.clone(), args.next().unwrap_or_default()))
+            .collect::<HashMap<_, _>>();
+        if let Some(name) = &closure.function.name {
+            values
+                .entry(name.clone())
+                .or_insert_with(|| function.clone());
         }
+        let scope = scope.call(closure.bindings.clone(), values);
+        match &closure.function.body {
+            FunctionBody::Expression(body) => self.evaluate_expr(body, &scope),
+            FunctionBody::Block(statements) => {
+                let mut last = JsValue::Undefined;
+                match self.execute_block(statements, &scope, &mut last)? {
+                    Completion::Return(value) => Ok(value),
+                    Completion::Normal => Ok(JsValue::Undefined),
+                }
+            }
+        }
+    }
+    /// Converts evaluated arguments to the JSON values host functions receive.
+    fn host_arguments(&self, args: Vec<JsValue>) -> Result<Vec<Value>> {
+        args.iter()
+            .map(|arg| arg.to_json(self.output_policy))
+            .collect()
     }
     #[cfg(feature = "string")]
-    fn evaluate_str_method(callee: &str, callee_name: &str, args: Vec<Value>) -> Result<Value> {
+    fn evaluate_str_method(callee: &str, callee_name: &str, args: Vec<JsValue>) -> Result<JsValue> {
         use method::string::StringMethod;
 
         let str_method = StringMethod::new(args);
         match callee_name {
             "replace" => str_method.replace(callee),
+            "replaceAll" => str_method.replace_all(callee),
             "contains" => str_method.contains(callee),
+            "includes" => str_method.includes(callee),
             "split" => str_method.split(callee),
+            "match" => str_method.match_regexp(callee),
+            "matchAll" => str_method.match_all(callee),
+            "search" => str_method.search(callee),
             "indexOf" => str_method.index_of(callee),
             "lastIndexOf" => str_method.last_index_of(callee),
             "toUpperCase" => str_method.to_upper_case(callee),
             "toLowerCase" => str_method.to_lower_case(callee),
             "substring" => str_method.substring(callee),
+            "slice" => str_method.slice(callee),
+            "charAt" => str_method.char_at(callee),
+            "at" => str_method.at(callee),
+            "charCodeAt" => str_method.char_code_at(callee),
+            "codePointAt" => str_method.code_point_at(callee),
+            "padStart" => str_method.pad_start(callee),
+            "padEnd" => str_method.pad_end(callee),
+            "repeat" => str_method.repeat(callee),
             "startsWith" => str_method.starts_with(callee),
             "endsWith" => str_method.ends_with(callee),
             "regexReplace" => str_method.regex_replace(callee),
             "length" => str_method.length(callee),
             "trim" => str_method.trim(callee),
-            _ => {
-                bail!("Unknown string method: {}", callee_name);
-            }
+            "trimStart" => str_method.trim_start(callee),
+            "trimEnd" => str_method.trim_end(callee),
+            _ => Err(EvaluatorError::unknown_function(callee_name)),
+        }
+    }
+    #[cfg(feature = "string")]
+    fn evaluate_regexp_method(
+        callee: &regexp::RegExp,
+        callee_name: &str,
+        args: Vec<JsValue>,
+    ) -> Result<JsValue> {
+        use method::regexp::RegExpMethod;
+
+        let regexp_method = RegExpMethod::new(args);
+        match callee_name {
+            "test" => regexp_method.test(callee),
+            "exec" => regexp_method.exec(callee),
+            _ => Err(EvaluatorError::unknown_function(callee_name)),
         }
     }
     #[cfg(feature = "array")]
     fn evaluate_array_method(
-        callee: &Vec<Value>,
+        &self,
+        callee: &value::Array,
         callee_name: &str,
-        args: Vec<Value>,
-    ) -> Result<Value> {
+        args: Vec<JsValue>,
+        scope: &Scope,
+    ) -> Result<JsValue> {
         use method::array::ArrayMethod;
 
+        if let ("fill", Some(value)) = (callee_name, args.first()) {
+            scope.stored(&JsValue::Array(callee.clone()), value);
+        }
         let array_method = ArrayMethod::new(args);
+        let call = |function: &JsValue, args| self.call_function(function, args, scope);
         match callee_name {
-            "join" => array_method.join(callee),
-            _ => bail!("Unknown array method: {}", callee_name),
+            "join" => array_method.join(&callee.borrow()),
+            "includes" => array_method.includes(&callee.borrow()),
+            "indexOf" => array_method.index_of(&callee.borrow()),
+            "lastIndexOf" => array_method.last_index_of(&callee.borrow()),
+            "slice" => array_method.slice(&callee.borrow()),
+            "concat" => array_method.concat(&callee.borrow()),
+            "reverse" => array_method.reverse(callee),
+            "flat" => array_method.flat(callee),
+            "at" => array_method.at(&callee.borrow()),
+            "fill" => array_method.fill(callee),
+            "keys" => array_method.keys(&callee.borrow()),
+            "map" => array_method.map(callee, call),
+            "filter" => array_method.filter(callee, call),
+            "reduce" => array_method.reduce(callee, call),
+            "some" => array_method.some(callee, call),
+            "every" => array_method.every(callee, call),
+            "find" => array_method.find(callee, call),
+            "findIndex" => array_method.find_index(callee, call),
+            "flatMap" => array_method.flat_map(callee, call),
+            "sort" => array_method.sort(callee, call),
+            _ => Err(EvaluatorError::unknown_function(callee_name)),
         }
     }
     #[cfg(feature = "math")]
-    fn evaluate_math_function(callee_name: &str, mut args: Vec<Value>) -> Result<Value> {
-        let value = args.remove(0).take();
-        match args.len() {
-            1 => match callee_name {
+    fn evaluate_math_function(callee_name: &str, mut args: Vec<JsValue>) -> Result<JsValue> {
+        let value = args.remove(0);
+        let result = match args.len() {
+            0 => match callee_name {
                 "floor" => math::unary_function(value, f64::floor),
                 "ceil" => math::unary_function(value, f64::ceil),
                 "round" => math::unary_function(value, f64::round),
@@ -439,10 +1337,10 @@ impl Evaluator {
                 "abs" => math::unary_function(value, f64::abs),
                 "clamp" => math::unary_function(value, |x| x.clamp(0.0, 1.0)),
                 "bitwiseNot" => unary_bitwise_not(value),
-                _ => bail!("{:?} not found in function context", callee_name),
+                _ => return Err(EvaluatorError::unknown_function(callee_name)),
             },
-            2 => {
-                let second = args.remove(0).take();
+            1 => {
+                let second = args.remove(0);
                 match callee_name {
                     "atan2" => math::binary_function(value, second, f64::atan2),
                     "min" => math::binary_function(value, second, f64::min),
@@ -453,10 +1351,67 @@ impl Evaluator {
                     "bitwiseOr" => bitwise_operation(value, second, |l, r| l | r),
                     "bitwiseLeft" => bitwise_operation(value, second, |l, r| l << (r & 0x1F)),
                     "bitwiseRight" => bitwise_operation(value, second, |l, r| l >> (r & 0x1F)),
-                    _ => bail!("{:?} not found in function context", callee_name),
+                    _ => return Err(EvaluatorError::unknown_function(callee_name)),
                 }
             }
-            _ => bail!("{:?} not found in function context", callee_name),
+            _ => return Err(EvaluatorError::unknown_function(callee_name)),
+        };
+        Ok(result)
+    }
+}
+
+/// How running a statement ended.
+enum Completion {
+    Normal,
+    Return(JsValue),
+}
+
+/// The place an assignment writes to, with the object and key of a member
+/// target already evaluated.
+enum Reference {
+    Variable(String),
+    Property { object: JsValue, key: JsValue },
+}
+
+/// Whether the left operand of a logical operator is its result, so the right
+/// one isn't evaluated.
+/// Applies `?.` to the evaluated `object` of a member expression.
+fn short_circuit_object(
+    object: &Expr,
+    value: Result<Option<JsValue>>,
+    optional: bool,
+) -> Result<Option<JsValue>> {
+    let value = match value {
+        Ok(Some(value)) => value,
+        Ok(None) => return Ok(None),
+        Err(EvaluatorError::VariableNotFound { .. })
+            if optional && matches!(object.kind, ExprKind::Identifier(_)) =>
+        {
+            return Ok(None)
         }
+        Err(e) => return Err(e),
+    };
+    if optional && value.is_nullish() {
+        return Ok(None);
+    }
+    Ok(Some(value))
+}
+
+fn short_circuits(operator: LogicalOperator, left: &JsValue) -> bool {
+    match operator {
+        LogicalOperator::And => !value_to_bool(left),
+        LogicalOperator::Or => value_to_bool(left),
+        LogicalOperator::Coalesce => !left.is_nullish(),
+    }
+}
+
+fn property_not_found(object: &JsValue, property: &str) -> EvaluatorError {
+    EvaluatorError::PropertyNotFound {
+        object: object
+            .to_json(OutputPolicy::default())
+            .unwrap_or_default()
+            .to_string(),
+        property: property.to_string(),
+        span: Span::default(),
     }
 }
@@ -1,31 +1,372 @@
-use anyhow::{anyhow, bail, Result};
-use serde_json::Value;
+use std::{cell::RefCell, cmp::Ordering, rc::Rc};
+
+use super::MethodArguments;
+use crate::{
+    bin_op::equality,
+    error::{EvaluatorError, Result},
+    util::{relative_index, value_to_bool, value_to_number, value_to_string, vec_to_js_string},
+    value::{Array, ArrayData, JsValue},
+};
 
 pub struct ArrayMethod {
-    args: Vec<Value>,
+    args: Vec<JsValue>,
+}
+
+impl MethodArguments for ArrayMethod {
+    fn args(&self) -> &[JsValue] {
+        &self.args
+    }
 }
 
 impl ArrayMethod {
-    pub fn new(args: Vec<Value>) -> Self {
+    pub fn new(args: Vec<JsValue>) -> Self {
         ArrayMethod { args }
     }
-    fn get_string_argument(&self, index: usize) -> Result<&str> {
-        self.args
-            .get(index)
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| anyhow!("Argument {} must be a string", index + 1))
+    fn get_callback(&self, function: &str) -> Result<&JsValue> {
+        match self.args.first() {
+            Some(callback @ JsValue::Function(_)) => Ok(callback),
+            _ => Err(EvaluatorError::argument_type(function, 0, "a function")),
+        }
     }
-    pub fn join(&self, arr: &Vec<Value>) -> Result<Value> {
-        if self.args.len() != 1 {
-            bail!("join method requires 1 argument")
+    /// Calls the callback with `(element, index, array)` for every element of a
+    /// snapshot of `arr`, until `visit` returns `false`.
+    fn for_each<F, V>(&self, function: &str, arr: &Array, mut call: F, mut visit: V) -> Result<()>
+    where
+        F: FnMut(&JsValue, Vec<JsValue>) -> Result<JsValue>,
+        V: FnMut(usize, &JsValue, JsValue) -> bool,
+    {
+        let callback = self.get_callback(function)?;
+        let items = arr.borrow().to_vec();
+        for (index, item) in items.iter().enumerate() {
+            let args = vec![
+                item.clone(),
+                JsValue::Number(index as f64),
+                JsValue::Array(arr.clone()),
+            ];
+            if !visit(index, item, call(callback, args)?) {
+                break;
+            }
         }
-        let delimiter = self.get_string_argument(0)?;
-        let result: Vec<String> = arr
+        Ok(())
+    }
+    /// Joins the elements with `separator`, `","` by default. `null` and
+    /// `undefined` become empty strings and nested arrays are joined with
+    /// commas, as in `String(arr)`.
+    pub fn join(&self, arr: &[JsValue]) -> Result<JsValue> {
+        self.check_argument_count("join", 0, 1)?;
+        let separator = match self.args.first() {
+            None | Some(JsValue::Undefined) => ",".to_string(),
+            Some(separator) => value_to_string(separator),
+        };
+        let joined = arr
+            .iter()
+            .map(|item| vec_to_js_string(std::slice::from_ref(item)))
+            .collect::<Vec<_>>()
+            .join(&separator);
+        Ok(JsValue::String(joined))
+    }
+    /// Uses `SameValueZero`, so unlike `indexOf` it finds `NaN`.
+    pub fn includes(&self, arr: &[JsValue]) -> Result<JsValue> {
+        self.check_argument_count("includes", 1, 2)?;
+        let search = &self.args[0];
+        let start = self.get_position_argument(1, 0.0, arr.len());
+        let found = arr[start..].iter().any(|item| match (item, search) {
+            (JsValue::Number(a), JsValue::Number(b)) => a == b || (a.is_nan() && b.is_nan()),
+            _ => equality(item, search, true),
+        });
+        Ok(JsValue::Bool(found))
+    }
+    pub fn index_of(&self, arr: &[JsValue]) -> Result<JsValue> {
+        self.check_argument_count("indexOf", 1, 2)?;
+        let search = &self.args[0];
+        let start = self.get_position_argument(1, 0.0, arr.len());
+        let index = arr[start..]
+            .iter()
+            .position(|item| equality(item, search, true))
+            .map_or(-1.0, |index| (start + index) as f64);
+        Ok(JsValue::Number(index))
+    }
+    pub fn last_index_of(&self, arr: &[JsValue]) -> Result<JsValue> {
+        self.check_argument_count("lastIndexOf", 1, 2)?;
+        let search = &self.args[0];
+        let from = self.get_integer_argument(1, arr.len() as f64 - 1.0);
+        let end = if from < 0.0 {
+            arr.len() as f64 + from + 1.0
+        } else {
+            (from + 1.0).min(arr.len() as f64)
+        };
+        let index = arr[..end.max(0.0) as usize]
             .iter()
-            .filter_map(|v| v.as_str())
-            .map(|s| s.to_string())
+            .rposition(|item| equality(item, search, true))
+            .map_or(-1.0, |index| index as f64);
+        Ok(JsValue::Number(index))
+    }
+    pub fn slice(&self, arr: &[JsValue]) -> Result<JsValue> {
+        self.check_argument_count("slice", 0, 2)?;
+        let start = self.get_position_argument(0, 0.0, arr.len());
+        let end = self.get_position_argument(1, arr.len() as f64, arr.len());
+        Ok(JsValue::array(arr[start..end.max(start)].to_vec()))
+    }
+    /// Appends the arguments, spreading the ones that are arrays.
+    pub fn concat(&self, arr: &[JsValue]) -> Result<JsValue> {
+        let mut result = arr.to_vec();
+        for arg in &self.args {
+            match arg {
+                JsValue::Array(other) => result.extend(other.borrow().iter().cloned()),
+                value => result.push(value.clone()),
+            }
+        }
+        Ok(JsValue::array(result))
+    }
+    /// Reverses `arr` in place and returns it.
+    pub fn reverse(&self, arr: &Array) -> Result<JsValue> {
+        self.check_argument_count("reverse", 0, 0)?;
+        arr.borrow_mut().reverse();
+        Ok(JsValue::Array(arr.clone()))
+    }
+    /// Flattening an array into itself is a `TypeError`, since it would
+    /// never end with a depth of `Infinity`.
+    pub fn flat(&self, arr: &Array) -> Result<JsValue> {
+        self.check_argument_count("flat", 0, 1)?;
+        let depth = self.get_integer_argument(0, 1.0);
+        let mut result = Vec::new();
+        flatten_into(&mut result, arr, depth, &mut Vec::new())?;
+        Ok(JsValue::array(result))
+    }
+    pub fn at(&self, arr: &[JsValue]) -> Result<JsValue> {
+        self.check_argument_count("at", 1, 1)?;
+        let index = self.get_integer_argument(0, 0.0);
+        if !index.is_finite() {
+            return Ok(JsValue::Undefined);
+        }
+        Ok(relative_index(index as i64, arr.len())
+            .map(|index| arr[index].clone())
+            .unwrap_or_default())
+    }
+    /// Fills `arr` in place between `start` and `end` and returns it.
+    pub fn fill(&self, arr: &Array) -> Result<JsValue> {
+        self.check_argument_count("fill", 1, 3)?;
+        let mut items = arr.borrow_mut();
+        let length = items.len();
+        let start = self.get_position_argument(1, 0.0, length);
+        let end = self.get_position_argument(2, length as f64, length);
+        for item in items.iter_mut().take(end).skip(start) {
+            *item = self.args[0].clone();
+        }
+        Ok(JsValue::Array(arr.clone()))
+    }
+    /// Returns the indices of `arr` as an array, since there are no
+    /// iterators.
+    pub fn keys(&self, arr: &[JsValue]) -> Result<JsValue> {
+        self.check_argument_count("keys", 0, 0)?;
+        let keys = (0..arr.len())
+            .map(|index| JsValue::Number(index as f64))
             .collect();
-        let joined = result.join(delimiter);
-        Ok(Value::String(joined))
+        Ok(JsValue::array(keys))
+    }
+    pub fn map<F>(&self, arr: &Array, call: F) -> Result<JsValue>
+    where
+        F: FnMut(&JsValue, Vec<JsValue>) -> Result<JsValue>,
+    {
+        let mut result = Vec::with_capacity(arr.borrow().len());
+        self.for_each("map", arr, call, |_, _, value| {
+            result.push(value);
+            true
+        })?;
+        Ok(JsValue::array(result))
+    }
+    pub fn filter<F>(&self, arr: &Array, call: F) -> Result<JsValue>
+    where
+        F: FnMut(&JsValue, Vec<JsValue>) -> Result<JsValue>,
+    {
+        let mut result = Vec::new();
+        self.for_each("filter", arr, call, |_, item, value| {
+            if value_to_bool(&value) {
+                result.push(item.clone());
+            }
+            true
+        })?;
+        Ok(JsValue::array(result))
+    }
+    pub fn flat_map<F>(&self, arr: &Array, call: F) -> Result<JsValue>
+    where
+        F: FnMut(&JsValue, Vec<JsValue>) -> Result<JsValue>,
+    {
+        let mut result = Vec::new();
+        self.for_each("flatMap", arr, call, |_, _, value| {
+            match value {
+                JsValue::Array(inner) => result.extend(inner.borrow().iter().cloned()),
+                value => result.push(value),
+            }
+            true
+        })?;
+        Ok(JsValue::array(result))
+    }
+    pub fn some<F>(&self, arr: &Array, call: F) -> Result<JsValue>
+    where
+        F: FnMut(&JsValue, Vec<JsValue>) -> Result<JsValue>,
+    {
+        let mut found = false;
+        self.for_each("some", arr, call, |_, _, value| {
+            found = value_to_bool(&value);
+            !found
+        })?;
+        Ok(JsValue::Bool(found))
+    }
+    pub fn every<F>(&self, arr: &Array, call: F) -> Result<JsValue>
+    where
+        F: FnMut(&JsValue, Vec<JsValue>) -> Result<JsValue>,
+    {
+        let mut all = true;
+        self.for_each("every", arr, call, |_, _, value| {
+            all = value_to_bool(&value);
+            all
+        })?;
+        Ok(JsValue::Bool(all))
+    }
+    pub fn find<F>(&self, arr: &Array, call: F) -> Result<JsValue>
+    where
+        F: FnMut(&JsValue, Vec<JsValue>) -> Result<JsValue>,
+    {
+        let mut found = JsValue::Undefined;
+        self.for_each("find", arr, call, |_, item, value| {
+            if value_to_bool(&value) {
+                found = item.clone();
+                return false;
+            }
+            true
+        })?;
+        Ok(found)
+    }
+    pub fn find_index<F>(&self, arr: &Array, call: F) -> Result<JsValue>
+    where
+        F: FnMut(&JsValue, Vec<JsValue>) -> Result<JsValue>,
+    {
+        let mut found = -1.0;
+        self.for_each("findIndex", arr, call, |index, _, value| {
+            if value_to_bool(&value) {
+                found = index as f64;
+                return false;
+            }
+            true
+        })?;
+        Ok(JsValue::Number(found))
+    }
+    pub fn reduce<F>(&self, arr: &Array, mut call: F) -> Result<JsValue>
+    where
+        F: FnMut(&JsValue, Vec<JsValue>) -> Result<JsValue>,
+    {
+        let callback = self.get_callback("reduce")?;
+        if self.args.len() > 2 {
+            return Err(EvaluatorError::argument_count(
+                "reduce",
+                "1 or 2",
+                self.args.len(),
+            ));
+        }
+        let items = arr.borrow().to_vec();
+        let mut items = items.into_iter().enumerate();
+        let mut accumulator = match self.args.get(1) {
+            Some(initial) => initial.clone(),
+            None => match items.next() {
+                Some((_, first)) => first,
+                None => {
+                    return Err(EvaluatorError::type_error(
+                        "Reduce of empty array with no initial value",
+                    ))
+                }
+            },
+        };
+        for (index, item) in items {
+            let args = vec![
+                accumulator,
+                item,
+                JsValue::Number(index as f64),
+                JsValue::Array(arr.clone()),
+            ];
+            accumulator = call(callback, args)?;
+        }
+        Ok(accumulator)
+    }
+    /// Sorts `arr` in place and returns it. Without a comparator, elements are
+    /// compared as strings. `undefined` elements always sort last.
+    pub fn sort<F>(&self, arr: &Array, mut call: F) -> Result<JsValue>
+    where
+        F: FnMut(&JsValue, Vec<JsValue>) -> Result<JsValue>,
+    {
+        let comparator = match self.args.first() {
+            None | Some(JsValue::Undefined) => None,
+            Some(_) => Some(self.get_callback("sort")?),
+        };
+        let (defined, undefined): (Vec<JsValue>, Vec<JsValue>) = arr
+            .borrow()
+            .iter()
+            .cloned()
+            .partition(|item| !matches!(item, JsValue::Undefined));
+        let mut sorted = merge_sort(defined, &mut |a, b| match comparator {
+            Some(comparator) => {
+                let order = value_to_number(&call(comparator, vec![a.clone(), b.clone()])?);
+                Ok(order.partial_cmp(&0.0).unwrap_or(Ordering::Equal))
+            }
+            None => Ok(value_to_string(a)
+                .encode_utf16()
+                .cmp(value_to_string(b).encode_utf16())),
+        })?;
+        sorted.extend(undefined);
+        **arr.borrow_mut() = sorted;
+        Ok(JsValue::Array(arr.clone()))
+    }
+}
+
+/// Appends the elements of `arr` to `result`. `ancestors` are the arrays
+/// being flattened further up.
+fn flatten_into(
+    result: &mut Vec<JsValue>,
+    arr: &Array,
+    depth: f64,
+    ancestors: &mut Vec<*const RefCell<ArrayData>>,
+) -> Result<()> {
+    if ancestors.contains(&Rc::as_ptr(arr)) {
+        return Err(EvaluatorError::type_error(
+            "Cannot flatten an array that contains itself",
+        ));
+    }
+    ancestors.push(Rc::as_ptr(arr));
+    for item in arr.borrow().iter() {
+        match item {
+            JsValue::Array(inner) if depth >= 1.0 => {
+                flatten_into(result, inner, depth - 1.0, ancestors)?
+            }
+            item => result.push(item.clone()),
+        }
+    }
+    ancestors.pop();
+    Ok(())
+}
+
+/// A stable merge sort. Unlike `slice::sort_by`, it tolerates comparators
+/// that are inconsistent or fail part way through.
+fn merge_sort<F>(mut items: Vec<JsValue>, compare: &mut F) -> Result<Vec<JsValue>>
+where
+    F: FnMut(&JsValue, &JsValue) -> Result<Ordering>,
+{
+    if items.len() <= 1 {
+        return Ok(items);
+    }
+    let right = items.split_off(items.len() / 2);
+    let left = merge_sort(items, compare)?;
+    let right = merge_sort(right, compare)?;
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    while let (Some(l), Some(r)) = (left.peek(), right.peek()) {
+        if compare(l, r)? == Ordering::Greater {
+            merged.extend(right.next());
+        } else {
+            merged.extend(left.next());
+        }
     }
+    merged.extend(left);
+    merged.extend(right);
+    Ok(merged)
 }
@@ -1,4 +1,58 @@
 #[cfg(feature = "array")]
 pub mod array;
 #[cfg(feature = "string")]
+pub mod regexp;
+#[cfg(feature = "string")]
 pub mod string;
+
+use crate::{
+    error::{EvaluatorError, Result},
+    util::value_to_number,
+    value::JsValue,
+};
+
+/// Argument handling shared by the built-in methods.
+pub trait MethodArguments {
+    fn args(&self) -> &[JsValue];
+
+    fn check_argument_count(&self, function: &str, min: usize, max: usize) -> Result<()> {
+        let count = self.args().len();
+        if (min..=max).contains(&count) {
+            return Ok(());
+        }
+        let expected = if min == max {
+            min.to_string()
+        } else {
+            format!("{} to {}", min, max)
+        };
+        Err(EvaluatorError::argument_count(function, expected, count))
+    }
+
+    /// Returns argument `index` converted with `ToIntegerOrInfinity`, or
+    /// `default` when it is missing or `undefined`.
+    fn get_integer_argument(&self, index: usize, default: f64) -> f64 {
+        match self.args().get(index) {
+            None | Some(JsValue::Undefined) => default,
+            Some(value) => {
+                let number = value_to_number(value);
+                if number.is_nan() {
+                    0.0
+                } else {
+                    number.trunc()
+                }
+            }
+        }
+    }
+
+    /// Returns argument `index` as a position in a sequence of `length`
+    /// elements: negative values count back from the end, and the result is
+    /// clamped to `0..=length`.
+    fn get_position_argument(&self, index: usize, default: f64, length: usize) -> usize {
+        let position = self.get_integer_argument(index, default);
+        if position < 0.0 {
+            (length as f64 + position).max(0.0) as usize
+        } else {
+            position.min(length as f64) as usize
+        }
+    }
+}
@@ -0,0 +1,300 @@
+use std::collections::BTreeMap;
+
+use regex::Captures;
+
+use crate::{
+    error::{EvaluatorError, Result},
+    regexp::RegExp,
+    util::value_to_string,
+    value::JsValue,
+};
+
+/// Regular expression methods, plus the string methods that take a regular
+/// expression. Indices in results are counted in UTF-16 code units, like the
+/// string methods.
+pub struct RegExpMethod {
+    args: Vec<JsValue>,
+}
+
+impl RegExpMethod {
+    pub fn new(args: Vec<JsValue>) -> Self {
+        RegExpMethod { args }
+    }
+
+    fn get_input(&self, function: &str) -> Result<String> {
+        if self.args.len() != 1 {
+            return Err(EvaluatorError::argument_count(
+                function,
+                "1",
+                self.args.len(),
+            ));
+        }
+        Ok(value_to_string(&self.args[0]))
+    }
+
+    pub fn test(&self, re: &RegExp) -> Result<JsValue> {
+        let input = self.get_input("test")?;
+        Ok(JsValue::Bool(re.regex.is_match(&input)))
+    }
+
+    /// Returns the first match, or `null`. See [`match_result`].
+    pub fn exec(&self, re: &RegExp) -> Result<JsValue> {
+        let input = self.get_input("exec")?;
+        Ok(re.regex.captures(&input).map_or(JsValue::Null, |captures| {
+            match_result(re, &input, &captures)
+        }))
+    }
+}
+
+/// `s.match(re)`: with the `g` flag, every matched string, otherwise the first
+/// match like `re.exec(s)`. `null` when nothing matches.
+pub fn match_regexp(re: &RegExp, s: &str) -> JsValue {
+    if !re.global() {
+        return re
+            .regex
+            .captures(s)
+            .map_or(JsValue::Null, |captures| match_result(re, s, &captures));
+    }
+    let matches = re
+        .regex
+        .find_iter(s)
+        .map(|m| JsValue::from(m.as_str()))
+        .collect::<Vec<JsValue>>();
+    if matches.is_empty() {
+        return JsValue::Null;
+    }
+    JsValue::array(matches)
+}
+
+/// `s.matchAll(re)`: every match, each like the result of `re.exec(s)`.
+pub fn match_all(re: &RegExp, s: &str) -> Result<JsValue> {
+    if !re.global() {
+        return Err(EvaluatorError::type_error(
+            "matchAll must be called with a global RegExp",
+        ));
+    }
+    Ok(JsValue::array(
+        re.regex
+            .captures_iter(s)
+            .map(|captures| match_result(re, s, &captures))
+            .collect(),
+    ))
+}
+
+/// `s.search(re)`: the index of the first match, or `-1`.
+pub fn search(re: &RegExp, s: &str) -> JsValue {
+    JsValue::Number(
+        re.regex
+            .find(s)
+            .map_or(-1.0, |m| utf16_index(s, m.start()) as f64),
+    )
+}
+
+/// Replaces the first match, or every match when `all` is set. The
+/// replacement may refer to the match with JavaScript's `$` patterns: `$$`,
+/// `$&`, `` $` ``, `$'`, `$1` to `$99` and `$<name>`.
+pub fn replace(re: &RegExp, s: &str, replacement: &str, all: bool) -> JsValue {
+    let limit = if all { 0 } else { 1 };
+    JsValue::String(
+        re.regex
+            .replacen(s, limit, |captures: &Captures| {
+                substitute(re, s, captures, replacement)
+            })
+            .into_owned(),
+    )
+}
+
+/// Splits `s` around the matches of `re`, like `s.split(re, limit)`. Capture
+/// groups are included in the result between the parts they separate, and an
+/// empty match doesn't split at the start or the end of `s`.
+pub fn split(re: &RegExp, s: &str, limit: usize) -> JsValue {
+    let mut parts = Vec::new();
+    if limit == 0 {
+        return JsValue::array(parts);
+    }
+    if s.is_empty() {
+        if !re.regex.is_match(s) {
+            parts.push(JsValue::from(s));
+        }
+        return JsValue::array(parts);
+    }
+    // `start` is the end of the last match and `position` where to look for
+    // the next one.
+    let (mut start, mut position) = (0, 0);
+    while position < s.len() {
+        let Some(captures) = re.regex.captures_at(s, position) else {
+            break;
+        };
+        let whole = captures.get(0).expect("capture group 0 always matches");
+        if whole.start() >= s.len() {
+            break;
+        }
+        if whole.end() == start {
+            position = next_char_boundary(s, whole.start());
+            continue;
+        }
+        parts.push(JsValue::from(&s[start..whole.start()]));
+        for group in captures.iter().skip(1) {
+            parts.push(group.map_or(JsValue::Undefined, |m| JsValue::from(m.as_str())));
+        }
+        if parts.len() >= limit {
+            parts.truncate(limit);
+            return JsValue::array(parts);
+        }
+        start = whole.end();
+        position = start;
+    }
+    parts.push(JsValue::from(&s[start..]));
+    parts.truncate(limit);
+    JsValue::array(parts)
+}
+
+/// The array `exec` returns: the matched string followed by the capture
+/// groups, `undefined` for those that didn't participate. It also has the
+/// `index` and `input` properties, and `groups`, an object of the named groups
+/// or `undefined` when there are none.
+fn match_result(re: &RegExp, input: &str, captures: &Captures) -> JsValue {
+    let whole = captures.get(0).expect("capture group 0 always matches");
+    let elements = captures
+        .iter()
+        .map(|group| group.map_or(JsValue::Undefined, |m| JsValue::from(m.as_str())))
+        .collect();
+    let groups = re
+        .regex
+        .capture_names()
+        .flatten()
+        .map(|name| {
+            let value = captures
+                .name(name)
+                .map_or(JsValue::Undefined, |m| JsValue::from(m.as_str()));
+            (name.to_string(), value)
+        })
+        .collect::<BTreeMap<String, JsValue>>();
+    let groups = if groups.is_empty() {
+        JsValue::Undefined
+    } else {
+        JsValue::object(groups)
+    };
+    let properties = BTreeMap::from([
+        (
+            "index".to_string(),
+            JsValue::Number(utf16_index(input, whole.start()) as f64),
+        ),
+        ("input".to_string(), JsValue::from(input)),
+        ("groups".to_string(), groups),
+    ]);
+    JsValue::array_with_properties(elements, properties)
+}
+
+/// Expands the `$` patterns of `replacement` for a match of a string rather
+/// than a regular expression, between `before` and `after`. There are no
+/// capture groups, so only `$$`, `$&`, `` $` `` and `$'` are replaced.
+pub fn substitute_str(replacement: &str, before: &str, matched: &str, after: &str) -> String {
+    expand(replacement, before, matched, after, None)
+}
+
+fn substitute(re: &RegExp, input: &str, captures: &Captures, replacement: &str) -> String {
+    let whole = captures.get(0).expect("capture group 0 always matches");
+    expand(
+        replacement,
+        &input[..whole.start()],
+        whole.as_str(),
+        &input[whole.end()..],
+        Some((re, captures)),
+    )
+}
+
+/// Expands the `$` patterns of `replacement` for one match, like
+/// JavaScript's `GetSubstitution`. Patterns that don't refer to anything are
+/// kept as they are.
+fn expand(
+    replacement: &str,
+    before: &str,
+    matched: &str,
+    after: &str,
+    captures: Option<(&RegExp, &Captures)>,
+) -> String {
+    let group_count = captures.map_or(0, |(_, captures)| captures.len() - 1);
+    let has_names =
+        captures.is_some_and(|(re, _)| re.regex.capture_names().flatten().next().is_some());
+    let mut result = String::with_capacity(replacement.len());
+    let mut rest = replacement;
+    while let Some(dollar) = rest.find('$') {
+        result.push_str(&rest[..dollar]);
+        rest = &rest[dollar + 1..];
+        let next = rest.chars().next();
+        let consumed = match next {
+            Some('$') => {
+                result.push('$');
+                1
+            }
+            Some('&') => {
+                result.push_str(matched);
+                1
+            }
+            Some('`') => {
+                result.push_str(before);
+                1
+            }
+            Some('\'') => {
+                result.push_str(after);
+                1
+            }
+            Some('0'..='9') => {
+                let bytes = rest.as_bytes();
+                let one = (bytes[0] - b'0') as usize;
+                let two = bytes
+                    .get(1)
+                    .filter(|b| b.is_ascii_digit())
+                    .map(|b| one * 10 + (b - b'0') as usize);
+                // `$12` is group 12 when there is one, otherwise group 1
+                // followed by "2".
+                let (group, length) = match two {
+                    Some(two) if (1..=group_count).contains(&two) => (two, 2),
+                    _ => (one, 1),
+                };
+                if let Some((_, captures)) = captures.filter(|_| (1..=group_count).contains(&group))
+                {
+                    if let Some(m) = captures.get(group) {
+                        result.push_str(m.as_str());
+                    }
+                    length
+                } else {
+                    result.push('$');
+                    0
+                }
+            }
+            Some('<') if has_names => match (rest.find('>'), captures) {
+                (Some(end), Some((_, captures))) => {
+                    if let Some(m) = captures.name(&rest[1..end]) {
+                        result.push_str(m.as_str());
+                    }
+                    end + 1
+                }
+                _ => {
+                    result.push('$');
+                    0
+                }
+            },
+            _ => {
+                result.push('$');
+                0
+            }
+        };
+        rest = &rest[consumed..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Converts a byte offset into `s` to a UTF-16 index.
+fn utf16_index(s: &str, byte_index: usize) -> usize {
+    s[..byte_index].encode_utf16().count()
+}
+
+fn next_char_boundary(s: &str, index: usize) -> usize {
+    s[index..]
+        .chars()
+        .next()
+        .map_or(index + 1, |c| index + c.len_utf8())
+}
@@ -1,144 +1,461 @@
-use anyhow::{anyhow, bail, Result};
 use regex::Regex;
-use serde_json::Value;
 
+use super::{regexp, MethodArguments};
+use crate::{
+    error::{EvaluatorError, Result},
+    regexp::RegExp,
+    util::{relative_index, value_to_number, value_to_uint32},
+    value::JsValue,
+};
+
+/// Strings longer than this many UTF-16 code units are rejected instead of
+/// being allocated, like JavaScript's "Invalid string length".
+const MAX_STRING_LENGTH: usize = 1 << 29;
+
+/// String methods. Indices and lengths are counted in UTF-16 code units, as
+/// in JavaScript, and out of range indices are clamped rather than panicking.
+/// Slicing through a surrogate pair leaves a lone surrogate, which is
+/// replaced with U+FFFD.
 pub struct StringMethod {
-    args: Vec<Value>,
+    args: Vec<JsValue>,
+}
+
+impl MethodArguments for StringMethod {
+    fn args(&self) -> &[JsValue] {
+        &self.args
+    }
 }
 
 impl StringMethod {
-    pub fn new(args: Vec<Value>) -> Self {
+    pub fn new(args: Vec<JsValue>) -> Self {
         StringMethod { args }
     }
 
-    fn get_string_argument(&self, index: usize) -> Result<&str> {
+    fn get_string_argument(&self, function: &str, index: usize) -> Result<&str> {
         self.args
             .get(index)
             .and_then(|v| v.as_str())
-            .ok_or_else(|| anyhow!("Argument {} must be a string", index + 1))
+            .ok_or_else(|| EvaluatorError::argument_type(function, index, "a string"))
     }
 
-    fn get_number_argument(&self, index: usize) -> Result<usize> {
-        self.args
-            .get(index)
-            .and_then(|v| v.as_f64())
-            .map(|v| v as usize)
-            .ok_or_else(|| anyhow!("Argument {} must be a number", index + 1))
+    /// Returns the regular expression in argument 0, or compiles a string
+    /// argument with `flags`, as `new RegExp(pattern, flags)` would.
+    fn get_regexp_argument(&self, function: &str, flags: &str) -> Result<RegExp> {
+        match self.args.first() {
+            Some(JsValue::RegExp(re)) => Ok(RegExp::clone(re)),
+            _ => RegExp::new(self.get_string_argument(function, 0)?, flags),
+        }
     }
 
-    pub fn replace(&self, s: &str) -> Result<Value> {
+    /// Returns argument `index` clamped to `0..=length`.
+    fn get_clamped_argument(&self, index: usize, default: f64, length: usize) -> usize {
+        self.get_integer_argument(index, default)
+            .clamp(0.0, length as f64) as usize
+    }
+
+    /// Replaces the first occurrence of a string, like JavaScript. A regular
+    /// expression replaces its first match, or every match with the `g` flag.
+    /// Either way the replacement may use `$` patterns such as `$&`.
+    pub fn replace(&self, s: &str) -> Result<JsValue> {
         if self.args.len() != 2 {
-            bail!("replace method requires 2 arguments");
+            return Err(EvaluatorError::argument_count(
+                "replace",
+                "2",
+                self.args.len(),
+            ));
+        }
+        let new = self.get_string_argument("replace", 1)?;
+        if let Some(JsValue::RegExp(re)) = self.args.first() {
+            return Ok(regexp::replace(re, s, new, re.global()));
         }
-        let old = self.get_string_argument(0)?;
-        let new = self.get_string_argument(1)?;
-        Ok(Value::String(s.replace(old, new)))
+        let old = self.get_string_argument("replace", 0)?;
+        let Some(start) = s.find(old) else {
+            return Ok(JsValue::from(s));
+        };
+        let end = start + old.len();
+        let mut result = s[..start].to_string();
+        result.push_str(&regexp::substitute_str(new, &s[..start], old, &s[end..]));
+        result.push_str(&s[end..]);
+        Ok(JsValue::String(result))
     }
 
-    pub fn contains(&self, s: &str) -> Result<Value> {
-        if self.args.len() != 1 {
-            bail!("contains method requires 1 argument");
+    pub fn replace_all(&self, s: &str) -> Result<JsValue> {
+        if self.args.len() != 2 {
+            return Err(EvaluatorError::argument_count(
+                "replaceAll",
+                "2",
+                self.args.len(),
+            ));
+        }
+        let new = self.get_string_argument("replaceAll", 1)?;
+        if let Some(JsValue::RegExp(re)) = self.args.first() {
+            if !re.global() {
+                return Err(EvaluatorError::type_error(
+                    "replaceAll must be called with a global RegExp",
+                ));
+            }
+            return Ok(regexp::replace(re, s, new, true));
+        }
+        let old = self.get_string_argument("replaceAll", 0)?;
+        if old.is_empty() {
+            // Like JavaScript, insert `new` between every UTF-16 code unit.
+            let units = utf16(s);
+            let expand = |i: usize| {
+                let before = String::from_utf16_lossy(&units[..i]);
+                let after = String::from_utf16_lossy(&units[i..]);
+                regexp::substitute_str(new, &before, "", &after)
+            };
+            let mut result = expand(0);
+            for i in 0..units.len() {
+                result.push_str(&String::from_utf16_lossy(&units[i..i + 1]));
+                result.push_str(&expand(i + 1));
+            }
+            return Ok(JsValue::String(result));
+        }
+        let mut result = String::with_capacity(s.len());
+        let mut last = 0;
+        for (start, _) in s.match_indices(old) {
+            let end = start + old.len();
+            result.push_str(&s[last..start]);
+            result.push_str(&regexp::substitute_str(new, &s[..start], old, &s[end..]));
+            last = end;
         }
-        let substring = self.get_string_argument(0)?;
-        Ok(Value::Bool(s.contains(substring)))
+        result.push_str(&s[last..]);
+        Ok(JsValue::String(result))
     }
 
-    pub fn split(&self, s: &str) -> Result<Value> {
+    pub fn contains(&self, s: &str) -> Result<JsValue> {
         if self.args.len() != 1 {
-            bail!("split method requires 1 argument");
+            return Err(EvaluatorError::argument_count(
+                "contains",
+                "1",
+                self.args.len(),
+            ));
         }
-        let delimiter = self.get_string_argument(0)?;
-        let result: Vec<String> = if delimiter.is_empty() {
-            s.chars().map(|c| c.to_string()).collect()
-        } else {
-            s.split(delimiter).map(|s| s.to_string()).collect()
+        let substring = self.get_string_argument("contains", 0)?;
+        Ok(JsValue::Bool(s.contains(substring)))
+    }
+
+    pub fn includes(&self, s: &str) -> Result<JsValue> {
+        self.check_argument_count("includes", 1, 2)?;
+        let search = utf16(self.get_string_argument("includes", 0)?);
+        let units = utf16(s);
+        let start = self.get_clamped_argument(1, 0.0, units.len());
+        Ok(JsValue::Bool(find_units(&units, &search, start).is_some()))
+    }
+
+    /// Splits on `separator`, a string or a regular expression. Without one
+    /// the result is `[s]`; an empty separator splits into UTF-16 code units.
+    /// `limit` caps the number of parts returned.
+    pub fn split(&self, s: &str) -> Result<JsValue> {
+        self.check_argument_count("split", 0, 2)?;
+        let limit = match self.args.get(1) {
+            None | Some(JsValue::Undefined) => u32::MAX,
+            Some(limit) => value_to_uint32(limit),
+        } as usize;
+        let parts: Vec<String> = match self.args.first() {
+            None | Some(JsValue::Undefined) => vec![s.to_string()],
+            Some(JsValue::RegExp(re)) => return Ok(regexp::split(re, s, limit)),
+            Some(_) => {
+                let separator = self.get_string_argument("split", 0)?;
+                if separator.is_empty() {
+                    utf16(s)
+                        .chunks(1)
+                        .take(limit)
+                        .map(String::from_utf16_lossy)
+                        .collect()
+                } else {
+                    s.split(separator).take(limit).map(str::to_string).collect()
+                }
+            }
         };
-        Ok(Value::Array(
-            result.into_iter().map(Value::String).collect(),
+        Ok(JsValue::array(
+            parts.into_iter().map(JsValue::String).collect(),
         ))
     }
 
-    pub fn index_of(&self, s: &str) -> Result<Value> {
-        if self.args.len() != 1 {
-            bail!("indexOf method requires 1 argument");
-        }
-        let substring = self.get_string_argument(0)?;
-        Ok(Value::Number(
-            s.find(substring).map_or(-1, |index| index as i32).into(),
+    /// Like `re.exec(s)`, or every matched string with the `g` flag. A string
+    /// argument is used as a pattern.
+    pub fn match_regexp(&self, s: &str) -> Result<JsValue> {
+        self.check_argument_count("match", 1, 1)?;
+        let re = self.get_regexp_argument("match", "")?;
+        Ok(regexp::match_regexp(&re, s))
+    }
+
+    /// Every match of a regular expression with the `g` flag. A string
+    /// argument is used as a pattern.
+    pub fn match_all(&self, s: &str) -> Result<JsValue> {
+        self.check_argument_count("matchAll", 1, 1)?;
+        let re = self.get_regexp_argument("matchAll", "g")?;
+        regexp::match_all(&re, s)
+    }
+
+    /// The index of the first match of a regular expression, or `-1`. A
+    /// string argument is used as a pattern.
+    pub fn search(&self, s: &str) -> Result<JsValue> {
+        self.check_argument_count("search", 1, 1)?;
+        let re = self.get_regexp_argument("search", "")?;
+        Ok(regexp::search(&re, s))
+    }
+
+    pub fn index_of(&self, s: &str) -> Result<JsValue> {
+        self.check_argument_count("indexOf", 1, 2)?;
+        let search = utf16(self.get_string_argument("indexOf", 0)?);
+        let units = utf16(s);
+        let start = self.get_clamped_argument(1, 0.0, units.len());
+        Ok(JsValue::Number(
+            find_units(&units, &search, start).map_or(-1.0, |index| index as f64),
         ))
     }
 
-    pub fn last_index_of(&self, s: &str) -> Result<Value> {
-        if self.args.len() != 1 {
-            bail!("lastIndexOf method requires 1 argument");
+    pub fn last_index_of(&self, s: &str) -> Result<JsValue> {
+        self.check_argument_count("lastIndexOf", 1, 2)?;
+        let search = utf16(self.get_string_argument("lastIndexOf", 0)?);
+        let units = utf16(s);
+        let position = match self.args.get(1).map(value_to_number) {
+            Some(position) if !position.is_nan() => position.trunc(),
+            _ => f64::INFINITY,
+        };
+        let start = position.clamp(0.0, units.len() as f64) as usize;
+        let index = (0..=start)
+            .rev()
+            .find(|&index| units[index..].starts_with(&search));
+        Ok(JsValue::Number(index.map_or(-1.0, |index| index as f64)))
+    }
+
+    pub fn to_upper_case(&self, s: &str) -> Result<JsValue> {
+        if !self.args.is_empty() {
+            return Err(EvaluatorError::argument_count(
+                "toUpperCase",
+                "0",
+                self.args.len(),
+            ));
         }
-        let substring = self.get_string_argument(0)?;
-        Ok(Value::Number(
-            s.rfind(substring).map_or(-1, |index| index as i32).into(),
-        ))
+        Ok(JsValue::String(s.to_uppercase()))
     }
 
-    pub fn to_upper_case(&self, s: &str) -> Result<Value> {
-        if self.args.len() != 0 {
-            bail!("toUpperCase method requires no arguments");
+    pub fn to_lower_case(&self, s: &str) -> Result<JsValue> {
+        if !self.args.is_empty() {
+            return Err(EvaluatorError::argument_count(
+                "toLowerCase",
+                "0",
+                self.args.len(),
+            ));
         }
-        Ok(Value::String(s.to_uppercase()))
+        Ok(JsValue::String(s.to_lowercase()))
     }
 
-    pub fn to_lower_case(&self, s: &str) -> Result<Value> {
-        if self.args.len() != 0 {
-            bail!("toLowerCase method requires no arguments");
+    /// Negative and `NaN` indices are treated as `0`, and the bounds are
+    /// swapped when `start > end`.
+    pub fn substring(&self, s: &str) -> Result<JsValue> {
+        self.check_argument_count("substring", 1, 2)?;
+        let units = utf16(s);
+        let start = self.get_clamped_argument(0, 0.0, units.len());
+        let end = self.get_clamped_argument(1, units.len() as f64, units.len());
+        let (start, end) = (start.min(end), start.max(end));
+        Ok(JsValue::String(String::from_utf16_lossy(
+            &units[start..end],
+        )))
+    }
+
+    /// Negative indices count back from the end.
+    pub fn slice(&self, s: &str) -> Result<JsValue> {
+        self.check_argument_count("slice", 0, 2)?;
+        let units = utf16(s);
+        let start = self.get_position_argument(0, 0.0, units.len());
+        let end = self.get_position_argument(1, units.len() as f64, units.len());
+        Ok(JsValue::String(String::from_utf16_lossy(
+            &units[start..end.max(start)],
+        )))
+    }
+
+    pub fn char_at(&self, s: &str) -> Result<JsValue> {
+        self.check_argument_count("charAt", 0, 1)?;
+        let units = utf16(s);
+        let index = self.get_integer_argument(0, 0.0);
+        let unit = (0.0..units.len() as f64)
+            .contains(&index)
+            .then(|| String::from_utf16_lossy(&units[index as usize..=index as usize]));
+        Ok(JsValue::String(unit.unwrap_or_default()))
+    }
+
+    pub fn at(&self, s: &str) -> Result<JsValue> {
+        self.check_argument_count("at", 1, 1)?;
+        let units = utf16(s);
+        let index = self.get_integer_argument(0, 0.0);
+        if !index.is_finite() {
+            return Ok(JsValue::Undefined);
         }
-        Ok(Value::String(s.to_lowercase()))
+        Ok(relative_index(index as i64, units.len())
+            .map(|index| JsValue::String(String::from_utf16_lossy(&units[index..=index])))
+            .unwrap_or_default())
+    }
+
+    pub fn char_code_at(&self, s: &str) -> Result<JsValue> {
+        self.check_argument_count("charCodeAt", 0, 1)?;
+        let units = utf16(s);
+        let index = self.get_integer_argument(0, 0.0);
+        let code = (0.0..units.len() as f64)
+            .contains(&index)
+            .then(|| units[index as usize] as f64);
+        Ok(JsValue::Number(code.unwrap_or(f64::NAN)))
     }
 
-    pub fn substring(&self, s: &str) -> Result<Value> {
-        let start = self.get_number_argument(0)?;
-        let end = if self.args.len() == 2 {
-            self.get_number_argument(1)?
-        } else {
-            s.len()
+    /// Returns the full code point when `index` is the start of a surrogate
+    /// pair, and the code unit otherwise.
+    pub fn code_point_at(&self, s: &str) -> Result<JsValue> {
+        self.check_argument_count("codePointAt", 0, 1)?;
+        let units = utf16(s);
+        let index = self.get_integer_argument(0, 0.0);
+        if !(0.0..units.len() as f64).contains(&index) {
+            return Ok(JsValue::Undefined);
+        }
+        let index = index as usize;
+        let code_point = char::decode_utf16(units[index..].iter().copied())
+            .next()
+            .and_then(|c| c.ok())
+            .map_or(units[index] as u32, |c| c as u32);
+        Ok(JsValue::Number(code_point as f64))
+    }
+
+    pub fn pad_start(&self, s: &str) -> Result<JsValue> {
+        let padding = self.padding("padStart", s)?;
+        Ok(JsValue::String(padding + s))
+    }
+
+    pub fn pad_end(&self, s: &str) -> Result<JsValue> {
+        let padding = self.padding("padEnd", s)?;
+        Ok(JsValue::String(s.to_string() + &padding))
+    }
+
+    /// The fill needed to pad `s` to the target length in argument 0, made
+    /// of repetitions of argument 1 (a space by default).
+    fn padding(&self, function: &str, s: &str) -> Result<String> {
+        self.check_argument_count(function, 1, 2)?;
+        let target = self.get_integer_argument(0, 0.0);
+        let fill = match self.args.get(1) {
+            None | Some(JsValue::Undefined) => " ",
+            Some(_) => self.get_string_argument(function, 1)?,
         };
-        Ok(Value::String(s[start..end].to_string()))
+        let length = s.encode_utf16().count();
+        if target <= length as f64 || fill.is_empty() {
+            return Ok(String::new());
+        }
+        if target > MAX_STRING_LENGTH as f64 {
+            return Err(EvaluatorError::type_error("Invalid string length"));
+        }
+        let fill_length = target as usize - length;
+        let units = utf16(fill)
+            .into_iter()
+            .cycle()
+            .take(fill_length)
+            .collect::<Vec<u16>>();
+        Ok(String::from_utf16_lossy(&units))
     }
 
-    pub fn starts_with(&self, s: &str) -> Result<Value> {
+    pub fn repeat(&self, s: &str) -> Result<JsValue> {
+        self.check_argument_count("repeat", 1, 1)?;
+        let count = self.get_integer_argument(0, 0.0);
+        if count < 0.0 || count.is_infinite() {
+            return Err(EvaluatorError::argument_type(
+                "repeat",
+                0,
+                "a non-negative finite number",
+            ));
+        }
+        if count * s.encode_utf16().count() as f64 > MAX_STRING_LENGTH as f64 {
+            return Err(EvaluatorError::type_error("Invalid string length"));
+        }
+        Ok(JsValue::String(s.repeat(count as usize)))
+    }
+
+    pub fn starts_with(&self, s: &str) -> Result<JsValue> {
         if self.args.len() != 1 {
-            bail!("startsWith method requires 1 argument");
+            return Err(EvaluatorError::argument_count(
+                "startsWith",
+                "1",
+                self.args.len(),
+            ));
         }
-        let prefix = self.get_string_argument(0)?;
-        Ok(Value::Bool(s.starts_with(prefix)))
+        let prefix = self.get_string_argument("startsWith", 0)?;
+        Ok(JsValue::Bool(s.starts_with(prefix)))
     }
 
-    pub fn ends_with(&self, s: &str) -> Result<Value> {
+    pub fn ends_with(&self, s: &str) -> Result<JsValue> {
         if self.args.len() != 1 {
-            bail!("endsWith method requires 1 argument");
+            return Err(EvaluatorError::argument_count(
+                "endsWith",
+                "1",
+                self.args.len(),
+            ));
         }
-        let suffix = self.get_string_argument(0)?;
-        Ok(Value::Bool(s.ends_with(suffix)))
+        let suffix = self.get_string_argument("endsWith", 0)?;
+        Ok(JsValue::Bool(s.ends_with(suffix)))
     }
 
-    pub fn regex_replace(&self, s: &str) -> Result<Value> {
+    pub fn regex_replace(&self, s: &str) -> Result<JsValue> {
         if self.args.len() != 2 {
-            bail!("regexReplace method requires 2 arguments");
+            return Err(EvaluatorError::argument_count(
+                "regexReplace",
+                "2",
+                self.args.len(),
+            ));
         }
-        let pattern = self.get_string_argument(0)?;
-        let replacement = self.get_string_argument(1)?;
-        let re = Regex::new(pattern).map_err(|e| anyhow!(e))?;
-        Ok(Value::String(re.replace_all(s, replacement).to_string()))
+        let pattern = self.get_string_argument("regexReplace", 0)?;
+        let replacement = self.get_string_argument("regexReplace", 1)?;
+        let re = Regex::new(pattern).map_err(|e| {
+            EvaluatorError::argument_type("regexReplace", 0, format!("a valid regex ({})", e))
+        })?;
+        Ok(JsValue::String(re.replace_all(s, replacement).to_string()))
     }
 
-    pub fn length(&self, s: &str) -> Result<Value> {
-        if self.args.len() != 0 {
-            bail!("length method requires no arguments");
+    /// The number of UTF-16 code units, the same as the `length` property.
+    pub fn length(&self, s: &str) -> Result<JsValue> {
+        if !self.args.is_empty() {
+            return Err(EvaluatorError::argument_count(
+                "length",
+                "0",
+                self.args.len(),
+            ));
         }
-        Ok(Value::Number(s.chars().count().into()))
+        Ok(JsValue::Number(s.encode_utf16().count() as f64))
     }
 
-    pub fn trim(&self, s: &str) -> Result<Value> {
-        if self.args.len() != 0 {
-            bail!("trim method requires no arguments");
+    pub fn trim(&self, s: &str) -> Result<JsValue> {
+        if !self.args.is_empty() {
+            return Err(EvaluatorError::argument_count("trim", "0", self.args.len()));
         }
-        Ok(Value::String(s.trim().to_string()))
+        Ok(JsValue::String(
+            s.trim_matches(is_js_whitespace).to_string(),
+        ))
     }
+
+    pub fn trim_start(&self, s: &str) -> Result<JsValue> {
+        self.check_argument_count("trimStart", 0, 0)?;
+        Ok(JsValue::String(
+            s.trim_start_matches(is_js_whitespace).to_string(),
+        ))
+    }
+
+    pub fn trim_end(&self, s: &str) -> Result<JsValue> {
+        self.check_argument_count("trimEnd", 0, 0)?;
+        Ok(JsValue::String(
+            s.trim_end_matches(is_js_whitespace).to_string(),
+        ))
+    }
+}
+
+fn utf16(s: &str) -> Vec<u16> {
+    s.encode_utf16().collect()
+}
+
+/// Finds `needle` in `haystack` at or after `start`.
+fn find_units(haystack: &[u16], needle: &[u16], start: usize) -> Option<usize> {
+    (start..=haystack.len()).find(|&index| haystack[index..].starts_with(needle))
+}
+
+/// JavaScript's `WhiteSpace` and `LineTerminator`, which add the byte order
+/// mark to Unicode's whitespace.
+fn is_js_whitespace(c: char) -> bool {
+    c.is_whitespace() || c == '\u{feff}'
 }
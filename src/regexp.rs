@@ -0,0 +1,239 @@
+use std::fmt;
+
+use crate::{
+    error::{EvaluatorError, Result},
+    value::JsValue,
+};
+
+/// A regular expression value, created by a `/pattern/flags` literal.
+///
+/// With the `string` feature the pattern is translated to the syntax of the
+/// `regex` crate when the literal is compiled. The `regex` crate has no
+/// backtracking, so backreferences and lookaround assertions are rejected, as
+/// are the `d`, `v` and `y` flags.
+#[derive(Debug, Clone)]
+pub(crate) struct RegExp {
+    pub source: String,
+    pub flags: String,
+    #[cfg(feature = "string")]
+    pub regex: regex::Regex,
+}
+
+impl RegExp {
+    pub fn new(source: &str, flags: &str) -> Result<Self> {
+        if let Some(flag) = flags.chars().find(|c| !"gimsu".contains(*c)) {
+            return Err(EvaluatorError::unsupported(format!(
+                "Unsupported regular expression flag '{}'",
+                flag
+            )));
+        }
+        Ok(Self {
+            source: source.to_string(),
+            flags: flags.to_string(),
+            #[cfg(feature = "string")]
+            regex: translate::compile(source, flags)?,
+        })
+    }
+
+    #[cfg_attr(not(feature = "string"), allow(dead_code))]
+    pub fn global(&self) -> bool {
+        self.flags.contains('g')
+    }
+
+    /// Reads one of the flag properties, such as `re.ignoreCase`.
+    ///
+    /// `lastIndex` is always `0`: matching never updates it, so `test` and
+    /// `exec` always search from the start of the string.
+    pub fn property(&self, property: &str) -> Option<JsValue> {
+        let flag = match property {
+            "source" => return Some(JsValue::String(self.source.clone())),
+            "flags" => return Some(JsValue::String(self.flags.clone())),
+            "lastIndex" => return Some(JsValue::Number(0.0)),
+            "global" => 'g',
+            "ignoreCase" => 'i',
+            "multiline" => 'm',
+            "dotAll" => 's',
+            "unicode" => 'u',
+            _ => return None,
+        };
+        Some(JsValue::Bool(self.flags.contains(flag)))
+    }
+}
+
+impl PartialEq for RegExp {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source && self.flags == other.flags
+    }
+}
+
+impl fmt::Display for RegExp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "/{}/{}", self.source, self.flags)
+    }
+}
+
+#[cfg(feature = "string")]
+mod translate {
+    use std::{iter::Peekable, str::Chars};
+
+    use regex::{Regex, RegexBuilder};
+
+    use crate::error::{EvaluatorError, Result};
+
+    // JavaScript's `\d` and `\w` are ASCII only, unlike the `regex` crate's.
+    const DIGIT: &str = "[0-9]";
+    const NOT_DIGIT: &str = "[^0-9]";
+    const WORD: &str = "[0-9A-Za-z_]";
+    const NOT_WORD: &str = "[^0-9A-Za-z_]";
+    // `\s` also matches the byte order mark.
+    const SPACE: &str = r"[\s\x{FEFF}]";
+    const NOT_SPACE: &str = r"[^\s\x{FEFF}]";
+    // Without the `s` flag, `.` matches anything but a line terminator.
+    const DOT: &str = r"[^\n\r\x{2028}\x{2029}]";
+
+    pub(super) fn compile(source: &str, flags: &str) -> Result<Regex> {
+        let pattern = translate(source, flags.contains('s'))?;
+        RegexBuilder::new(&pattern)
+            .case_insensitive(flags.contains('i'))
+            .multi_line(flags.contains('m'))
+            .build()
+            .map_err(|e| {
+                EvaluatorError::unsupported(format!(
+                    "Invalid regular expression /{}/{}: {}",
+                    source, flags, e
+                ))
+            })
+    }
+
+    /// Rewrites a JavaScript pattern where its meaning differs from the
+    /// `regex` crate's.
+    fn translate(source: &str, dot_all: bool) -> Result<String> {
+        let mut pattern = String::with_capacity(source.len());
+        let mut chars = source.chars().peekable();
+        let mut in_class = false;
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => translate_escape(&mut chars, in_class, &mut pattern)?,
+                '[' if in_class => pattern.push_str(r"\["),
+                // Characters that start set operations in the `regex` crate.
+                '&' | '~' if in_class => {
+                    pattern.push('\\');
+                    pattern.push(c);
+                }
+                ']' if in_class => {
+                    in_class = false;
+                    pattern.push(']');
+                }
+                '[' => {
+                    let negated = chars.next_if_eq(&'^').is_some();
+                    if chars.next_if_eq(&']').is_some() {
+                        // `[]` never matches and `[^]` matches anything.
+                        pattern.push_str(if negated {
+                            r"[\x{0}-\x{10FFFF}]"
+                        } else {
+                            r"[^\x{0}-\x{10FFFF}]"
+                        });
+                    } else {
+                        in_class = true;
+                        pattern.push_str(if negated { "[^" } else { "[" });
+                    }
+                }
+                '.' if !dot_all => pattern.push_str(DOT),
+                '.' => pattern.push_str("(?s:.)"),
+                '(' if chars.peek() == Some(&'?') => {
+                    let rest = chars.clone().skip(1).take(2).collect::<String>();
+                    if rest.starts_with('=')
+                        || rest.starts_with('!')
+                        || rest == "<="
+                        || rest == "<!"
+                    {
+                        return Err(EvaluatorError::unsupported(
+                            "Lookaround assertions are not supported in regular expressions",
+                        ));
+                    }
+                    pattern.push('(');
+                }
+                c => pattern.push(c),
+            }
+        }
+        Ok(pattern)
+    }
+
+    fn translate_escape(
+        chars: &mut Peekable<Chars>,
+        in_class: bool,
+        pattern: &mut String,
+    ) -> Result<()> {
+        let Some(c) = chars.next() else {
+            return Err(EvaluatorError::unsupported(
+                "Regular expression ends with '\\'",
+            ));
+        };
+        match c {
+            'd' => pattern.push_str(DIGIT),
+            'D' => pattern.push_str(NOT_DIGIT),
+            'w' => pattern.push_str(WORD),
+            'W' => pattern.push_str(NOT_WORD),
+            's' => pattern.push_str(SPACE),
+            'S' => pattern.push_str(NOT_SPACE),
+            // Inside a class, `\b` is a backspace.
+            'b' if in_class => pattern.push_str(r"\x08"),
+            'b' => pattern.push_str(r"(?-u:\b)"),
+            'B' => pattern.push_str(r"(?-u:\B)"),
+            '0' if !chars.peek().is_some_and(char::is_ascii_digit) => pattern.push_str(r"\x00"),
+            '1'..='9' | 'k' => {
+                return Err(EvaluatorError::unsupported(
+                    "Backreferences are not supported in regular expressions",
+                ))
+            }
+            'c' if chars.peek().is_some_and(char::is_ascii_alphabetic) => {
+                let letter = chars.next().unwrap_or_default();
+                push_char(pattern, char::from(letter as u8 % 32));
+            }
+            'u' => push_char(pattern, unicode_escape(chars)?),
+            'x' | 'f' | 'n' | 'r' | 't' | 'v' | 'p' | 'P' => {
+                pattern.push('\\');
+                pattern.push(c);
+            }
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+        Ok(())
+    }
+
+    /// Reads the rest of a `\uXXXX` or `\u{X}` escape. A surrogate pair
+    /// written as two escapes is combined into one character.
+    fn unicode_escape(chars: &mut Peekable<Chars>) -> Result<char> {
+        let invalid =
+            || EvaluatorError::unsupported("Invalid Unicode escape in regular expression");
+        let code = if chars.next_if_eq(&'{').is_some() {
+            let digits = chars.by_ref().take_while(|c| *c != '}').collect::<String>();
+            u32::from_str_radix(&digits, 16).map_err(|_| invalid())?
+        } else {
+            hex4(chars).ok_or_else(invalid)?
+        };
+        if (0xd800..0xdc00).contains(&code) {
+            let mut lookahead = chars.clone();
+            if lookahead.next() == Some('\\') && lookahead.next() == Some('u') {
+                if let Some(low @ 0xdc00..=0xdfff) = hex4(&mut lookahead) {
+                    *chars = lookahead;
+                    let code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                    return char::from_u32(code).ok_or_else(invalid);
+                }
+            }
+        }
+        // Strings never contain lone surrogates, so they can't be matched.
+        char::from_u32(code).ok_or_else(invalid)
+    }
+
+    fn hex4(chars: &mut Peekable<Chars>) -> Option<u32> {
+        let digits = chars.by_ref().take(4).collect::<String>();
+        if digits.len() != 4 {
+            return None;
+        }
+        u32::from_str_radix(&digits, 16).ok()
+    }
+
+    fn push_char(pattern: &mut String, c: char) {
+        pattern.push_str(&format!(r"\x{{{:X}}}", c as u32));
+    }
+}
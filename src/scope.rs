@@ -0,0 +1,335 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap, HashSet},
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    context::{ContextEntry, ContextProvider},
+    error::{EvaluatorError, Result},
+    value::{ArrayData, JsValue},
+};
+
+/// A chain of context providers, searched from the innermost overlay outwards.
+///
+/// A chain lives for a single evaluation. Each variable is converted from JSON
+/// the first time it is read and then reused, so every reference to the same
+/// context array or object is the same JavaScript value: `a === a` holds while
+/// `a === b` is `false` even when both hold equal JSON, just like two distinct
+/// objects in JavaScript.
+///
+/// The chain also keeps track of the bindings captured by function values and
+/// of the arrays and objects that were given other arrays, objects or
+/// functions, and clears them when the evaluation ends. Values that refer to
+/// themselves, as in `const f = n => f(n - 1)` or `o.self = o`, would otherwise
+/// never be freed.
+pub(crate) struct ContextChain<'a> {
+    provider: &'a dyn ContextProvider,
+    parent: Option<&'a ContextChain<'a>>,
+    values: RefCell<HashMap<String, JsValue>>,
+    retained: RefCell<Vec<Retained>>,
+}
+
+/// Something that may be part of a reference cycle once a value is stored in
+/// it.
+enum Retained {
+    Bindings(Weak<Bindings>),
+    Array(Weak<RefCell<ArrayData>>),
+    Object(Weak<RefCell<BTreeMap<String, JsValue>>>),
+}
+
+impl Retained {
+    fn is(&self, other: &Retained) -> bool {
+        match (self, other) {
+            (Retained::Bindings(a), Retained::Bindings(b)) => a.ptr_eq(b),
+            (Retained::Array(a), Retained::Array(b)) => a.ptr_eq(b),
+            (Retained::Object(a), Retained::Object(b)) => a.ptr_eq(b),
+            _ => false,
+        }
+    }
+
+    /// Drops the values held by the container if it is still alive. Dropping
+    /// them may drop other containers, so it must not happen while this one
+    /// is borrowed.
+    fn clear(self) {
+        match self {
+            Retained::Bindings(bindings) => {
+                if let Some(bindings) = bindings.upgrade() {
+                    bindings.clear();
+                }
+            }
+            Retained::Array(arr) => {
+                if let Some(arr) = arr.upgrade() {
+                    let data = std::mem::take(&mut *arr.borrow_mut());
+                    drop(data);
+                }
+            }
+            Retained::Object(map) => {
+                if let Some(map) = map.upgrade() {
+                    let entries = std::mem::take(&mut *map.borrow_mut());
+                    drop(entries);
+                }
+            }
+        }
+    }
+}
+
+impl<'a> ContextChain<'a> {
+    pub fn new(provider: &'a dyn ContextProvider) -> Self {
+        Self {
+            provider,
+            parent: None,
+            values: RefCell::default(),
+            retained: RefCell::default(),
+        }
+    }
+
+    /// Creates a chain whose entries shadow the ones in `self`.
+    pub fn child(&'a self, provider: &'a dyn ContextProvider) -> Self {
+        Self {
+            provider,
+            parent: Some(self),
+            values: RefCell::default(),
+            retained: RefCell::default(),
+        }
+    }
+
+    pub fn variable(&self, name: &str) -> Option<JsValue> {
+        if let Some(value) = self.values.borrow().get(name) {
+            return Some(value.clone());
+        }
+        match self.provider.variable(name) {
+            Some(value) => {
+                let value = JsValue::from_json(&value);
+                self.values
+                    .borrow_mut()
+                    .insert(name.to_string(), value.clone());
+                Some(value)
+            }
+            None => self.parent.and_then(|parent| parent.variable(name)),
+        }
+    }
+
+    pub fn function(&self, name: &str) -> Option<&'a ContextEntry> {
+        self.provider
+            .function(name)
+            .or_else(|| self.parent.and_then(|parent| parent.function(name)))
+    }
+
+    fn retain(&self, container: Retained) {
+        let mut retained = self.retained.borrow_mut();
+        // Function values created in a loop or callback share their bindings,
+        // and loops tend to store into the same array or object.
+        if retained.last().is_some_and(|last| last.is(&container)) {
+            return;
+        }
+        retained.push(container);
+    }
+}
+
+impl Drop for ContextChain<'_> {
+    fn drop(&mut self) {
+        for container in self.retained.get_mut().drain(..) {
+            container.clear();
+        }
+    }
+}
+
+/// Variables bound inside an expression, such as arrow function parameters or
+/// the `let` and `const` declarations of a script block.
+///
+/// Bindings are reference counted so a function value can keep the bindings it
+/// was created in alive after the enclosing call returns.
+#[derive(Debug, Default)]
+pub(crate) struct Bindings {
+    values: RefCell<HashMap<String, JsValue>>,
+    constants: RefCell<HashSet<String>>,
+    parent: Option<Rc<Bindings>>,
+}
+
+impl Bindings {
+    fn variable(&self, name: &str) -> Option<JsValue> {
+        match self.values.borrow().get(name) {
+            Some(value) => Some(value.clone()),
+            None => self.parent.as_ref()?.variable(name),
+        }
+    }
+
+    /// Drops the values of these bindings and of their parents.
+    fn clear(&self) {
+        // Dropping a value may drop other bindings, so it must not happen
+        // while the values are borrowed.
+        let values = std::mem::take(&mut *self.values.borrow_mut());
+        drop(values);
+        if let Some(parent) = &self.parent {
+            parent.clear();
+        }
+    }
+
+    /// Sets the innermost binding called `name`. Returns `false` if there is
+    /// none.
+    fn assign(&self, name: &str, value: JsValue) -> Result<bool> {
+        if let Some(binding) = self.values.borrow_mut().get_mut(name) {
+            if self.constants.borrow().contains(name) {
+                return Err(EvaluatorError::type_error(format!(
+                    "Assignment to constant variable '{}'",
+                    name
+                )));
+            }
+            *binding = value;
+            return Ok(true);
+        }
+        match &self.parent {
+            Some(parent) => parent.assign(name, value),
+            None => Ok(false),
+        }
+    }
+}
+
+/// What identifiers resolve to while evaluating: local bindings first, then
+/// the context.
+///
+/// A mutable scope also has top-level variables. Assigning to a name that
+/// isn't bound locally sets a variable there, shadowing the context, while
+/// other scopes only allow assigning to local bindings.
+#[derive(Clone)]
+pub(crate) struct Scope<'a> {
+    context: &'a ContextChain<'a>,
+    bindings: Option<Rc<Bindings>>,
+    variables: Option<Rc<Bindings>>,
+    depth: usize,
+}
+
+impl<'a> Scope<'a> {
+    pub fn new(context: &'a ContextChain<'a>) -> Self {
+        Self {
+            context,
+            bindings: None,
+            variables: None,
+            depth: 0,
+        }
+    }
+
+    /// Creates a mutable scope whose top-level variables start as `values`.
+    pub fn with_variables(context: &'a ContextChain<'a>, values: HashMap<String, JsValue>) -> Self {
+        let variables = Rc::new(Bindings {
+            values: RefCell::new(values),
+            ..Bindings::default()
+        });
+        Self {
+            context,
+            bindings: Some(variables.clone()),
+            variables: Some(variables),
+            depth: 0,
+        }
+    }
+
+    /// Creates a scope where `values` shadow `parent` and the context.
+    pub fn child(&self, parent: Option<Rc<Bindings>>, values: HashMap<String, JsValue>) -> Self {
+        Self {
+            context: self.context,
+            bindings: Some(Rc::new(Bindings {
+                values: RefCell::new(values),
+                constants: RefCell::default(),
+                parent,
+            })),
+            variables: self.variables.clone(),
+            depth: self.depth,
+        }
+    }
+
+    /// Creates the scope a function value runs in, one call deeper than this
+    /// one.
+    pub fn call(&self, parent: Option<Rc<Bindings>>, values: HashMap<String, JsValue>) -> Self {
+        Self {
+            depth: self.depth + 1,
+            ..self.child(parent, values)
+        }
+    }
+
+    /// How many function value calls this scope is nested in.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The local bindings visible in this scope, for a child scope.
+    pub fn bindings(&self) -> Option<Rc<Bindings>> {
+        self.bindings.clone()
+    }
+
+    /// The local bindings visible in this scope, for a function value to
+    /// capture. They are cleared when the evaluation ends.
+    pub fn capture(&self) -> Option<Rc<Bindings>> {
+        if let Some(bindings) = &self.bindings {
+            self.context
+                .retain(Retained::Bindings(Rc::downgrade(bindings)));
+        }
+        self.bindings.clone()
+    }
+
+    /// Records that `value` was stored in `container`, so the container is
+    /// cleared when the evaluation ends in case that made it refer to itself.
+    pub fn stored(&self, container: &JsValue, value: &JsValue) {
+        if !value.is_object() {
+            return;
+        }
+        match container {
+            JsValue::Array(arr) => self.context.retain(Retained::Array(Rc::downgrade(arr))),
+            JsValue::Object(map) => self.context.retain(Retained::Object(Rc::downgrade(map))),
+            _ => {}
+        }
+    }
+
+    pub fn variable(&self, name: &str) -> Option<JsValue> {
+        self.bindings
+            .as_ref()
+            .and_then(|bindings| bindings.variable(name))
+            .or_else(|| self.context.variable(name))
+    }
+
+    pub fn function(&self, name: &str) -> Option<&'a ContextEntry> {
+        self.context.function(name)
+    }
+
+    /// Declares a `let` or `const` variable in the innermost bindings, which
+    /// are created by [`Scope::child`].
+    pub fn declare(&self, name: &str, value: JsValue, constant: bool) {
+        if let Some(bindings) = &self.bindings {
+            bindings.values.borrow_mut().insert(name.to_string(), value);
+            if constant {
+                bindings.constants.borrow_mut().insert(name.to_string());
+            }
+        }
+    }
+
+    /// Assigns to a local binding or, in a mutable scope, to a top-level
+    /// variable.
+    pub fn assign(&self, name: &str, value: JsValue) -> Result<()> {
+        if let Some(bindings) = &self.bindings {
+            if bindings.assign(name, value.clone())? {
+                return Ok(());
+            }
+        }
+        match &self.variables {
+            Some(variables) => {
+                variables
+                    .values
+                    .borrow_mut()
+                    .insert(name.to_string(), value);
+                Ok(())
+            }
+            None => Err(EvaluatorError::unsupported(format!(
+                "Cannot assign to '{}', use Evaluator::evaluate_mut to assign to variables",
+                name
+            ))),
+        }
+    }
+
+    /// The top-level variables of a mutable scope.
+    pub fn variables(&self) -> HashMap<String, JsValue> {
+        self.variables
+            .as_ref()
+            .map(|variables| variables.values.borrow().clone())
+            .unwrap_or_default()
+    }
+}
@@ -1,9 +1,12 @@
-use anyhow::{anyhow, bail, Result};
 use semver::Version;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::util::value_to_number;
+use crate::{
+    error::{EvaluatorError, Result},
+    util::value_to_number,
+    value::{JsValue, OutputPolicy},
+};
 
 #[derive(Serialize, Deserialize)]
 pub struct SemverWrapper {
@@ -12,40 +15,57 @@ pub struct SemverWrapper {
 
 impl SemverWrapper {
     pub fn new(major: Value, minor: Value, patch: Value) -> Result<Self> {
-        let major = value_to_number(major)? as u64;
-        let minor = value_to_number(minor)? as u64;
-        let patch = value_to_number(patch)? as u64;
+        let major = value_to_number(&JsValue::from_json(&major)) as u64;
+        let minor = value_to_number(&JsValue::from_json(&minor)) as u64;
+        let patch = value_to_number(&JsValue::from_json(&patch)) as u64;
         Ok(SemverWrapper {
             version: Version::new(major, minor, patch),
         })
     }
+    /// Reads back a version object produced by `semver(...)`.
+    pub fn from_object(value: &JsValue) -> Option<Self> {
+        match value {
+            JsValue::Object(_) => {
+                let value = value.to_json(OutputPolicy::default()).ok()?;
+                Self::deserialize(&value).ok()
+            }
+            _ => None,
+        }
+    }
     pub fn from_value(value: Value) -> Result<Self> {
         match value {
             Value::String(value) => Ok(SemverWrapper {
-                version: Version::parse(&value)?,
+                version: Version::parse(&value).map_err(|e| {
+                    EvaluatorError::type_coercion(format!("Invalid semver {:?}: {}", value, e))
+                })?,
             }),
             Value::Array(value) => {
                 if let (Some(major), Some(minor), Some(patch)) =
-                    (value.get(0), value.get(1), value.get(2))
+                    (value.first(), value.get(1), value.get(2))
                 {
                     Self::new(major.to_owned(), minor.to_owned(), patch.to_owned())
                 } else {
-                    bail!("array requires size of 3")
+                    Err(EvaluatorError::type_coercion(
+                        "semver array requires size of 3",
+                    ))
                 }
             }
             Value::Object(mut value) => {
                 let major = value
                     .remove("major")
-                    .ok_or_else(|| anyhow!("Missing 'major' field"))?;
+                    .ok_or_else(|| EvaluatorError::type_coercion("Missing 'major' field"))?;
                 let minor = value
                     .remove("minor")
-                    .ok_or_else(|| anyhow!("Missing 'minor' field"))?;
+                    .ok_or_else(|| EvaluatorError::type_coercion("Missing 'minor' field"))?;
                 let patch = value
                     .remove("patch")
-                    .ok_or_else(|| anyhow!("Missing 'patch' field"))?;
+                    .ok_or_else(|| EvaluatorError::type_coercion("Missing 'patch' field"))?;
                 Self::new(major, minor, patch)
             }
-            _ => bail!("unsupported value type for semver parser: {:?}", value),
+            _ => Err(EvaluatorError::type_coercion(format!(
+                "unsupported value type for semver parser: {:?}",
+                value
+            ))),
         }
     }
     pub fn from_values(mut args: Vec<Value>) -> Result<Self> {
@@ -57,7 +77,7 @@ impl SemverWrapper {
                 let major = args.pop().unwrap();
                 Self::new(major, minor, patch)
             }
-            _ => bail!("semver requires either 1 or 3 args"),
+            found => Err(EvaluatorError::argument_count("semver", "1 or 3", found)),
         }
     }
 }
@@ -1,7 +1,9 @@
 mod bitwise_not;
 mod negation;
 mod plus;
+mod type_of;
 
 pub use self::bitwise_not::*;
 pub use self::negation::*;
 pub use self::plus::*;
+pub use self::type_of::*;
@@ -0,0 +1,12 @@
+use crate::value::JsValue;
+
+pub fn type_of(value: &JsValue) -> &'static str {
+    match value {
+        JsValue::Undefined => "undefined",
+        JsValue::Bool(_) => "boolean",
+        JsValue::Number(_) => "number",
+        JsValue::String(_) => "string",
+        JsValue::Function(_) => "function",
+        JsValue::Null | JsValue::Array(_) | JsValue::Object(_) | JsValue::RegExp(_) => "object",
+    }
+}
@@ -1,80 +1,273 @@
-use anyhow::{anyhow, Result};
-use serde_json::{Number, Value};
+use oxc::span::Span;
+use std::{cell::RefCell, rc::Rc};
 
-use crate::unary::unary_plus;
+use crate::{
+    error::{EvaluatorError, Result},
+    unary::type_of,
+    value::{number_to_string, ArrayData, JsValue},
+};
 
 pub static OBJ_STR: &str = "[object Object]";
-pub static NULL_STR: &str = "null";
 
-pub fn number_from_f64(value: f64) -> Result<Number> {
-    if value.fract() == 0.0 {
-        Ok(Number::from(value as i64))
-    } else {
-        Number::from_f64(value).ok_or_else(|| anyhow!("Invalid float number: {}", value))
-    }
+/// The longest an assignment may make an array, so a stray `list[1e9] = x`
+/// fails instead of allocating the holes before it.
+const MAX_ARRAY_LENGTH: usize = 1 << 24;
+
+pub fn vec_to_js_string(vec: &[JsValue]) -> String {
+    join_elements(vec, &mut Vec::new())
 }
 
-pub fn vec_to_js_string(vec: &[Value]) -> String {
+/// Like `Array.prototype.join`, an array nested inside itself, one of
+/// `ancestors`, converts to an empty string there.
+fn join_elements(vec: &[JsValue], ancestors: &mut Vec<*const RefCell<ArrayData>>) -> String {
     vec.iter()
         .map(|v| match v {
-            Value::Null => "".to_string(),
-            Value::Bool(b) => b.to_string(),
-            Value::Number(n) => n.to_string(),
-            Value::String(s) => s.clone(),
-            Value::Array(a) => vec_to_js_string(a), // arrays are recursively flattened
-            Value::Object(_) => OBJ_STR.to_string(),
+            JsValue::Undefined | JsValue::Null => "".to_string(),
+            JsValue::Array(arr) if ancestors.contains(&Rc::as_ptr(arr)) => "".to_string(),
+            JsValue::Array(arr) => {
+                ancestors.push(Rc::as_ptr(arr));
+                let joined = join_elements(&arr.borrow(), ancestors);
+                ancestors.pop();
+                joined
+            }
+            v => value_to_string(v),
         })
         .collect::<Vec<_>>()
         .join(",")
 }
 
-pub fn boolean_to_number(value: &bool) -> i32 {
-    if *value {
-        1
-    } else {
-        0
+/// ECMAScript `ToNumber`.
+pub fn value_to_number(value: &JsValue) -> f64 {
+    match value {
+        JsValue::Undefined => f64::NAN,
+        JsValue::Null => 0.0,
+        JsValue::Bool(b) => {
+            if *b {
+                1.0
+            } else {
+                0.0
+            }
+        }
+        JsValue::Number(n) => *n,
+        JsValue::String(s) => string_to_number(s),
+        JsValue::Array(_) | JsValue::Object(_) | JsValue::Function(_) | JsValue::RegExp(_) => {
+            value_to_number(&value_to_primitive(value))
+        }
+    }
+}
+
+/// ECMAScript `StringToNumber`: surrounding whitespace is ignored, the empty
+/// string is `0` and anything that isn't a numeric literal is `NaN`.
+pub fn string_to_number(s: &str) -> f64 {
+    let s = s.trim();
+    if s.is_empty() {
+        return 0.0;
+    }
+    let radix = match s.get(..2) {
+        Some("0x") | Some("0X") => Some(16),
+        Some("0o") | Some("0O") => Some(8),
+        Some("0b") | Some("0B") => Some(2),
+        _ => None,
+    };
+    if let Some(radix) = radix {
+        return u64::from_str_radix(&s[2..], radix).map_or(f64::NAN, |n| n as f64);
+    }
+    match s {
+        "Infinity" | "+Infinity" => f64::INFINITY,
+        "-Infinity" => f64::NEG_INFINITY,
+        // Rust accepts spellings like "inf" and "nan" that JavaScript doesn't.
+        _ if s.contains(|c: char| c.is_ascii_alphabetic() && c != 'e' && c != 'E') => f64::NAN,
+        _ => s.parse::<f64>().unwrap_or(f64::NAN),
     }
 }
 
-pub fn value_to_number(value: Value) -> Result<f64> {
+/// ECMAScript `ToString`.
+pub fn value_to_string(value: &JsValue) -> String {
     match value {
-        Value::Null => Ok(0.0),
-        Value::Bool(b) => Ok(if b { 1.0 } else { 0.0 }),
-        Value::Number(n) => Ok(n.as_f64().unwrap_or_default()),
-        Value::String(s) => Ok(s.parse::<f64>().unwrap_or(0.0)),
-        Value::Array(arr) => match unary_plus(Value::Array(arr))? {
-            Value::Number(n) => Ok(n.as_f64().unwrap_or_default()),
-            _ => Ok(0.0),
-        },
-        Value::Object(_) => Ok(f64::NAN),
+        JsValue::Undefined => "undefined".to_string(),
+        JsValue::Null => "null".to_string(),
+        JsValue::Bool(b) => b.to_string(),
+        JsValue::Number(n) => number_to_string(*n),
+        JsValue::String(s) => s.clone(),
+        JsValue::Array(_) => vec_to_js_string(std::slice::from_ref(value)),
+        JsValue::Object(_) => OBJ_STR.to_string(),
+        // The source text isn't kept after compiling.
+        JsValue::Function(_) => "function".to_string(),
+        JsValue::RegExp(re) => re.to_string(),
     }
 }
 
-pub fn value_to_string(value: Value) -> String {
+/// ECMAScript `ToPrimitive`. Arrays and objects have no `valueOf`, so they
+/// always convert to their string form.
+pub fn value_to_primitive(value: &JsValue) -> JsValue {
     match value {
-        Value::Null => NULL_STR.to_string(),
-        Value::Bool(b) => {
-            if b {
-                "true".to_string()
-            } else {
-                "false".to_string()
+        JsValue::Object(_) | JsValue::Array(_) | JsValue::Function(_) | JsValue::RegExp(_) => {
+            JsValue::String(value_to_string(value))
+        }
+        other => other.clone(),
+    }
+}
+
+/// ECMAScript `ToBoolean`.
+pub fn value_to_bool(value: &JsValue) -> bool {
+    match value {
+        JsValue::Undefined | JsValue::Null => false,
+        JsValue::Bool(b) => *b,
+        JsValue::Number(n) => *n != 0.0 && !n.is_nan(),
+        JsValue::String(s) => !s.is_empty(),
+        JsValue::Array(_) | JsValue::Object(_) | JsValue::Function(_) | JsValue::RegExp(_) => true,
+    }
+}
+
+/// ECMAScript `ToInt32`.
+pub fn value_to_int32(value: &JsValue) -> i32 {
+    let number = value_to_number(value);
+    if !number.is_finite() {
+        return 0;
+    }
+    (number.trunc().rem_euclid(4_294_967_296.0) as u32) as i32
+}
+
+/// ECMAScript `ToUint32`.
+pub fn value_to_uint32(value: &JsValue) -> u32 {
+    value_to_int32(value) as u32
+}
+
+pub fn is_string(value: &JsValue) -> bool {
+    matches!(value, JsValue::String(_))
+}
+
+/// Resolves an integer index the way `Array.prototype.at` does: negative
+/// indices count back from the end. Returns `None` when out of range.
+pub fn relative_index(index: i64, length: usize) -> Option<usize> {
+    let resolved = if index < 0 {
+        length as i64 + index
+    } else {
+        index
+    };
+    (0..length as i64)
+        .contains(&resolved)
+        .then_some(resolved as usize)
+}
+
+/// Returns the integer a computed member key refers to, for numbers and
+/// canonical numeric strings such as `"2"` or `"-1"`.
+pub fn value_to_index(key: &JsValue) -> Option<i64> {
+    let number = match key {
+        JsValue::Number(n) => *n,
+        JsValue::String(s) => {
+            let n = string_to_number(s);
+            if number_to_string(n) != *s {
+                return None;
             }
+            n
         }
-        Value::Number(n) => n.to_string(),
-        Value::String(s) => s,
-        Value::Array(arr) => vec_to_js_string(&arr),
-        Value::Object(_) => OBJ_STR.to_string(),
+        _ => return None,
+    };
+    (number.fract() == 0.0 && number.abs() < 9_007_199_254_740_992.0).then_some(number as i64)
+}
+
+/// The values produced by iterating `value`, as in `[...value]`: the elements
+/// of an array or the code points of a string. Nothing else is iterable.
+pub fn iterate(value: &JsValue) -> Result<Vec<JsValue>> {
+    match value {
+        JsValue::Array(arr) => Ok(arr.borrow().to_vec()),
+        JsValue::String(s) => Ok(s.chars().map(|c| JsValue::String(c.into())).collect()),
+        _ => Err(EvaluatorError::type_error(format!(
+            "{} is not iterable",
+            value_to_string(value)
+        ))),
     }
 }
 
-pub fn value_to_primitive(value: Value) -> Result<Value> {
+/// The own enumerable properties of `value`, as copied by `{ ...value }`.
+/// Arrays and strings have their indices; other primitives have none.
+pub fn own_properties(value: &JsValue) -> Vec<(String, JsValue)> {
     match value {
-        Value::Object(_) => Ok(Value::String(OBJ_STR.to_string())),
-        Value::Array(arr) => Ok(Value::String(vec_to_js_string(&arr))),
-        other => Ok(other),
+        JsValue::Object(map) => map
+            .borrow()
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect(),
+        JsValue::Array(arr) => {
+            let arr = arr.borrow();
+            let elements = arr.iter().enumerate();
+            let elements = elements.map(|(index, value)| (index.to_string(), value.clone()));
+            elements.chain(arr.properties.clone()).collect()
+        }
+        JsValue::String(s) => s
+            .encode_utf16()
+            .enumerate()
+            .map(|(index, unit)| {
+                let unit = String::from_utf16_lossy(&[unit]);
+                (index.to_string(), JsValue::String(unit))
+            })
+            .collect(),
+        _ => Vec::new(),
     }
 }
 
-pub fn is_string(value: &Value) -> bool {
-    matches!(value, Value::String(_))
+/// Sets `object[key]`, as in `object.key = value`. Array indices may be
+/// negative, counting back from the end like when reading them, and writing
+/// past the end fills the gap with holes. Setting `length` truncates or
+/// extends an array.
+pub fn set_property(object: &JsValue, key: &JsValue, value: JsValue) -> Result<()> {
+    match object {
+        JsValue::Object(map) => {
+            map.borrow_mut().insert(value_to_string(key), value);
+        }
+        JsValue::Array(arr) => {
+            let mut arr = arr.borrow_mut();
+            let length = arr.len();
+            match value_to_index(key) {
+                Some(index) if index >= 0 => {
+                    let index = index as usize;
+                    if index >= length {
+                        resize(&mut arr, index as f64 + 1.0)?;
+                    }
+                    arr[index] = value;
+                }
+                Some(index) => match relative_index(index, length) {
+                    Some(index) => arr[index] = value,
+                    None => {
+                        return Err(EvaluatorError::IndexOutOfRange {
+                            index,
+                            length,
+                            span: Span::default(),
+                        })
+                    }
+                },
+                None if matches!(key, JsValue::String(key) if key == "length") => {
+                    resize(&mut arr, value_to_number(&value))?;
+                }
+                None => {
+                    arr.properties.insert(value_to_string(key), value);
+                }
+            }
+        }
+        JsValue::Undefined | JsValue::Null => {
+            return Err(EvaluatorError::type_error(format!(
+                "Cannot set properties of {} (setting '{}')",
+                value_to_string(object),
+                value_to_string(key)
+            )))
+        }
+        _ => {
+            return Err(EvaluatorError::type_error(format!(
+                "Cannot create property '{}' on {} '{}'",
+                value_to_string(key),
+                type_of(object),
+                value_to_string(object)
+            )))
+        }
+    }
+    Ok(())
+}
+
+fn resize(arr: &mut ArrayData, length: f64) -> Result<()> {
+    if length.fract() != 0.0 || !(0.0..=MAX_ARRAY_LENGTH as f64).contains(&length) {
+        return Err(EvaluatorError::type_error("Invalid array length"));
+    }
+    arr.resize(length as usize, JsValue::Undefined);
+    Ok(())
 }
@@ -0,0 +1,305 @@
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    fmt,
+    ops::{Deref, DerefMut},
+    rc::Rc,
+    sync::Arc,
+};
+
+use serde_json::{Map, Number, Value};
+
+use crate::{
+    compiled::Function,
+    error::{EvaluatorError, Result},
+    regexp::RegExp,
+    scope::Bindings,
+};
+
+pub(crate) type Array = Rc<RefCell<ArrayData>>;
+pub(crate) type Object = Rc<RefCell<BTreeMap<String, JsValue>>>;
+
+/// A JavaScript value as seen while evaluating an expression.
+///
+/// Unlike `serde_json::Value` this can represent `undefined`, `NaN`,
+/// `±Infinity` and `-0`. Results are converted back to JSON with an
+/// [`OutputPolicy`] once evaluation finishes.
+#[derive(Debug, Clone, Default)]
+pub(crate) enum JsValue {
+    #[default]
+    Undefined,
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Array),
+    Object(Object),
+    Function(Rc<Closure>),
+    RegExp(Rc<RegExp>),
+}
+
+/// The elements of an array, which dereferences to them, together with any
+/// named properties set on it, such as the `index` and `groups` of a regular
+/// expression match.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ArrayData {
+    pub elements: Vec<JsValue>,
+    pub properties: BTreeMap<String, JsValue>,
+}
+
+impl Deref for ArrayData {
+    type Target = Vec<JsValue>;
+
+    fn deref(&self) -> &Vec<JsValue> {
+        &self.elements
+    }
+}
+
+impl DerefMut for ArrayData {
+    fn deref_mut(&mut self) -> &mut Vec<JsValue> {
+        &mut self.elements
+    }
+}
+
+/// A function value: a function expression together with the local bindings
+/// it closes over.
+pub(crate) struct Closure {
+    pub function: Arc<Function>,
+    pub bindings: Option<Rc<Bindings>>,
+}
+
+impl fmt::Debug for Closure {
+    // The bindings may contain the closure itself, so they aren't printed.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Closure")
+            .field("params", &self.function.params)
+            .finish_non_exhaustive()
+    }
+}
+
+impl JsValue {
+    pub fn array(values: Vec<JsValue>) -> Self {
+        Self::array_with_properties(values, BTreeMap::new())
+    }
+
+    pub fn array_with_properties(
+        elements: Vec<JsValue>,
+        properties: BTreeMap<String, JsValue>,
+    ) -> Self {
+        JsValue::Array(Rc::new(RefCell::new(ArrayData {
+            elements,
+            properties,
+        })))
+    }
+
+    pub fn object(map: BTreeMap<String, JsValue>) -> Self {
+        JsValue::Object(Rc::new(RefCell::new(map)))
+    }
+
+    pub fn from_json(value: &Value) -> Self {
+        match value {
+            Value::Null => JsValue::Null,
+            Value::Bool(b) => JsValue::Bool(*b),
+            Value::Number(n) => JsValue::Number(n.as_f64().unwrap_or(f64::NAN)),
+            Value::String(s) => JsValue::String(s.clone()),
+            Value::Array(arr) => JsValue::array(arr.iter().map(JsValue::from_json).collect()),
+            Value::Object(map) => JsValue::object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), JsValue::from_json(v)))
+                    .collect(),
+            ),
+        }
+    }
+
+    pub fn to_json(&self, policy: OutputPolicy) -> Result<Value> {
+        self.to_json_within(policy, &mut Vec::new())
+    }
+
+    /// Converts a value nested in `ancestors`, the arrays and objects being
+    /// converted further up. Containing one of them is an error, as in
+    /// `JSON.stringify`.
+    fn to_json_within(
+        &self,
+        policy: OutputPolicy,
+        ancestors: &mut Vec<*const ()>,
+    ) -> Result<Value> {
+        match self {
+            // `JSON.stringify` treats functions like `undefined`.
+            JsValue::Undefined | JsValue::Function(_) => match policy.undefined {
+                UndefinedOutput::Null => Ok(Value::Null),
+                UndefinedOutput::Error => Err(EvaluatorError::type_coercion(format!(
+                    "{} cannot be converted to JSON",
+                    if self.is_nullish() {
+                        "undefined"
+                    } else {
+                        "A function"
+                    }
+                ))),
+            },
+            JsValue::Null => Ok(Value::Null),
+            JsValue::Bool(b) => Ok(Value::Bool(*b)),
+            JsValue::Number(n) if n.is_finite() => Ok(Value::Number(json_number(*n))),
+            JsValue::Number(n) => match policy.non_finite {
+                NonFiniteOutput::Null => Ok(Value::Null),
+                NonFiniteOutput::String => Ok(Value::String(number_to_string(*n))),
+                NonFiniteOutput::Error => Err(EvaluatorError::type_coercion(format!(
+                    "{} cannot be converted to JSON",
+                    number_to_string(*n)
+                ))),
+            },
+            JsValue::String(s) => Ok(Value::String(s.clone())),
+            JsValue::Array(arr) => {
+                enter(ancestors, Rc::as_ptr(arr).cast())?;
+                let values = arr
+                    .borrow()
+                    .iter()
+                    .map(|v| v.to_json_within(policy, ancestors))
+                    .collect::<Result<Vec<Value>>>();
+                ancestors.pop();
+                Ok(Value::Array(values?))
+            }
+            // Like `JSON.stringify`, a regular expression has no enumerable
+            // properties.
+            JsValue::RegExp(_) => Ok(Value::Object(Map::new())),
+            JsValue::Object(map) => {
+                enter(ancestors, Rc::as_ptr(map).cast())?;
+                let entries = map
+                    .borrow()
+                    .iter()
+                    .map(|(k, v)| Ok((k.clone(), v.to_json_within(policy, ancestors)?)))
+                    .collect::<Result<Map<String, Value>>>();
+                ancestors.pop();
+                Ok(Value::Object(entries?))
+            }
+        }
+    }
+
+    pub fn is_nullish(&self) -> bool {
+        matches!(self, JsValue::Undefined | JsValue::Null)
+    }
+
+    /// Whether the value is compared by reference: an array, object, function
+    /// or regular expression.
+    pub fn is_object(&self) -> bool {
+        matches!(
+            self,
+            JsValue::Array(_) | JsValue::Object(_) | JsValue::Function(_) | JsValue::RegExp(_)
+        )
+    }
+
+    #[cfg_attr(not(feature = "string"), allow(dead_code))]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+impl From<bool> for JsValue {
+    fn from(value: bool) -> Self {
+        JsValue::Bool(value)
+    }
+}
+
+impl From<f64> for JsValue {
+    fn from(value: f64) -> Self {
+        JsValue::Number(value)
+    }
+}
+
+impl From<String> for JsValue {
+    fn from(value: String) -> Self {
+        JsValue::String(value)
+    }
+}
+
+impl From<&str> for JsValue {
+    fn from(value: &str) -> Self {
+        JsValue::String(value.to_string())
+    }
+}
+
+/// Adds the array or object at `pointer` to `ancestors`, unless it is already
+/// being converted.
+fn enter(ancestors: &mut Vec<*const ()>, pointer: *const ()) -> Result<()> {
+    if ancestors.contains(&pointer) {
+        return Err(EvaluatorError::type_error(
+            "Converting circular structure to JSON",
+        ));
+    }
+    ancestors.push(pointer);
+    Ok(())
+}
+
+/// Whole numbers become JSON integers, so `-0` is written as `0`.
+fn json_number(value: f64) -> Number {
+    if value.fract() == 0.0 && value.abs() < 9_007_199_254_740_992.0 {
+        Number::from(value as i64)
+    } else {
+        Number::from_f64(value).unwrap_or_else(|| Number::from(0))
+    }
+}
+
+/// Formats a number the way JavaScript's `Number.prototype.toString` does.
+pub(crate) fn number_to_string(value: f64) -> String {
+    if value.is_nan() {
+        return "NaN".to_string();
+    }
+    if value.is_infinite() {
+        return if value > 0.0 { "Infinity" } else { "-Infinity" }.to_string();
+    }
+    if value == 0.0 {
+        return "0".to_string();
+    }
+    let abs = value.abs();
+    if (1e-6..1e21).contains(&abs) {
+        return format!("{}", value);
+    }
+    let formatted = format!("{:e}", value);
+    match formatted.split_once('e') {
+        Some((mantissa, exponent)) if !exponent.starts_with('-') => {
+            format!("{}e+{}", mantissa, exponent)
+        }
+        _ => formatted,
+    }
+}
+
+/// How an evaluation result that has no JSON equivalent is converted to a
+/// `serde_json::Value`.
+///
+/// The default mirrors `JSON.stringify`: `undefined`, `NaN` and `±Infinity`
+/// become `null`. `-0` is always written as `0`. The policy also applies to
+/// the arguments passed to host functions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutputPolicy {
+    pub undefined: UndefinedOutput,
+    pub non_finite: NonFiniteOutput,
+}
+
+impl OutputPolicy {
+    /// Fails with [`EvaluatorError::TypeCoercion`] instead of losing
+    /// information.
+    pub const STRICT: OutputPolicy = OutputPolicy {
+        undefined: UndefinedOutput::Error,
+        non_finite: NonFiniteOutput::Error,
+    };
+}
+
+/// What `undefined` is converted to. See [`OutputPolicy`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UndefinedOutput {
+    #[default]
+    Null,
+    Error,
+}
+
+/// What `NaN`, `Infinity` and `-Infinity` are converted to. See [`OutputPolicy`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NonFiniteOutput {
+    #[default]
+    Null,
+    /// The JavaScript string form: `"NaN"`, `"Infinity"` or `"-Infinity"`.
+    String,
+    Error,
+}
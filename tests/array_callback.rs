@@ -0,0 +1,195 @@
+#![cfg(feature = "array")]
+
+use oxide_eval::{context::ContextEntry, Evaluator, EvaluatorError};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+fn evaluator() -> Evaluator {
+    let mut context = HashMap::new();
+    context.insert(
+        "cart".to_string(),
+        ContextEntry::Variable(json!({
+            "items": [
+                { "sku": "A", "price": 10, "qty": 2 },
+                { "sku": "X", "price": 5, "qty": 1 },
+                { "sku": "B", "price": 20, "qty": 3 },
+            ]
+        })),
+    );
+    context.insert("limit".to_string(), ContextEntry::Variable(json!(8)));
+    context.insert(
+        "double".to_string(),
+        ContextEntry::Function(Box::new(|args| {
+            json!(args.first().and_then(Value::as_f64).unwrap_or_default() * 2.0)
+        })),
+    );
+    Evaluator::new(context)
+}
+
+#[test]
+fn test_callbacks() {
+    let evaluator = evaluator();
+    assert_eq!(
+        evaluator
+            .evaluate("cart.items.some(i => i.sku == \"X\")")
+            .unwrap(),
+        true
+    );
+    assert_eq!(
+        evaluator
+            .evaluate("cart.items.every(i => i.price > limit)")
+            .unwrap(),
+        false
+    );
+    assert_eq!(
+        evaluator.evaluate("cart.items.map(i => i.sku)").unwrap(),
+        json!(["A", "X", "B"])
+    );
+    assert_eq!(
+        evaluator
+            .evaluate("cart.items.filter(i => i.price > limit).map(i => i.sku)")
+            .unwrap(),
+        json!(["A", "B"])
+    );
+    assert_eq!(
+        evaluator
+            .evaluate("cart.items.reduce((sum, i) => sum + i.price * i.qty, 0)")
+            .unwrap(),
+        85
+    );
+    assert_eq!(
+        evaluator
+            .evaluate("[1, 2, 3].reduce((a, b) => a + b)")
+            .unwrap(),
+        6
+    );
+    assert_eq!(
+        evaluator
+            .evaluate("cart.items.find(i => i.qty > 2).sku")
+            .unwrap(),
+        "B"
+    );
+    assert_eq!(
+        evaluator
+            .evaluate("cart.items.find(i => i.qty > 5)")
+            .unwrap(),
+        Value::Null
+    );
+    assert_eq!(
+        evaluator
+            .evaluate("cart.items.findIndex(i => i.sku == 'X')")
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        evaluator
+            .evaluate("cart.items.findIndex(i => i.sku == 'Z')")
+            .unwrap(),
+        -1
+    );
+    assert_eq!(
+        evaluator
+            .evaluate("[1, 2].flatMap(n => [n, n * 10])")
+            .unwrap(),
+        json!([1, 10, 2, 20])
+    );
+    assert_eq!(
+        evaluator
+            .evaluate("[1, 2, 3].map((n, i, all) => n * i + all.length)")
+            .unwrap(),
+        json!([3, 5, 9])
+    );
+    assert_eq!(
+        evaluator.evaluate("[1, 2].map(n => double(n))").unwrap(),
+        json!([2, 4])
+    );
+}
+
+#[test]
+fn test_callback_scope() {
+    let evaluator = evaluator();
+    // Parameters shadow the context, and nested arrows see outer parameters.
+    assert_eq!(
+        evaluator
+            .evaluate("[1, 2].map(limit => limit + 1)")
+            .unwrap(),
+        json!([2, 3])
+    );
+    assert_eq!(
+        evaluator
+            .evaluate("[1, 2].map(a => [10, 20].map(b => a + b + limit))")
+            .unwrap(),
+        json!([[19, 29], [20, 30]])
+    );
+    assert_eq!(
+        evaluator.evaluate("[1].map(() => limit)").unwrap(),
+        json!([8])
+    );
+    assert_eq!(
+        evaluator.evaluate("[1].map((a, b, c, d) => d)").unwrap(),
+        json!([null])
+    );
+    assert!(matches!(
+        evaluator.evaluate("[1].map(a => a) && a").unwrap_err(),
+        EvaluatorError::VariableNotFound { .. }
+    ));
+}
+
+#[test]
+fn test_sort() {
+    let evaluator = evaluator();
+    assert_eq!(
+        evaluator.evaluate("[3, 20, 100].sort()").unwrap(),
+        json!([100, 20, 3])
+    );
+    assert_eq!(
+        evaluator
+            .evaluate("[3, 20, 100].sort((a, b) => a - b)")
+            .unwrap(),
+        json!([3, 20, 100])
+    );
+    assert_eq!(
+        evaluator
+            .evaluate("cart.items.sort((a, b) => b.price - a.price).map(i => i.sku)")
+            .unwrap(),
+        json!(["B", "A", "X"])
+    );
+    // The sort is stable and tolerates inconsistent comparators.
+    assert_eq!(
+        evaluator
+            .evaluate("[[1, 'a'], [0, 'b'], [1, 'c']].sort((a, b) => a[0] - b[0]).map(p => p[1])")
+            .unwrap(),
+        json!(["b", "a", "c"])
+    );
+    assert_eq!(
+        evaluator
+            .evaluate("[3, 1, 2].sort(() => 1).length")
+            .unwrap(),
+        3
+    );
+    assert_eq!(
+        evaluator
+            .evaluate("[undefined, 2, 1].sort((a, b) => a - b)")
+            .unwrap(),
+        json!([1, 2, null])
+    );
+}
+
+#[test]
+fn test_callback_errors() {
+    let evaluator = evaluator();
+    assert!(matches!(
+        evaluator.evaluate("[1].map(1)").unwrap_err(),
+        EvaluatorError::InvalidArgumentType { index: 0, .. }
+    ));
+    assert!(matches!(
+        evaluator
+            .evaluate("[].reduce((a, b) => a + b)")
+            .unwrap_err(),
+        EvaluatorError::TypeError { .. }
+    ));
+    match evaluator.evaluate("[1].map(a => a.b.c)").unwrap_err() {
+        EvaluatorError::TypeError { span, .. } => assert_eq!((span.start, span.end), (13, 18)),
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
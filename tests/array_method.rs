@@ -1,3 +1,5 @@
+#![cfg(feature = "array")]
+
 #[cfg(test)]
 #[test]
 fn test_method() {
@@ -25,3 +27,110 @@ fn test_method() {
     assert_eq!(res1, "a b c");
     assert_eq!(res2, "1-2-3");
 }
+
+#[test]
+fn test_join() {
+    use oxide_eval::Evaluator;
+    use std::collections::HashMap;
+
+    let evaluator = Evaluator::new(HashMap::new());
+    assert_eq!(
+        evaluator
+            .evaluate("[1, 'a', true, null, undefined, [2, [3]]].join('-')")
+            .unwrap(),
+        "1-a-true---2,3"
+    );
+    assert_eq!(
+        evaluator.evaluate("[1, 2.5, -0].join()").unwrap(),
+        "1,2.5,0"
+    );
+    assert_eq!(evaluator.evaluate("[1, 2].join(0)").unwrap(), "102");
+    assert_eq!(evaluator.evaluate("[].join()").unwrap(), "");
+}
+
+#[test]
+fn test_non_callback_methods() {
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+
+    let mut context = HashMap::new();
+    context.insert(
+        "list".to_string(),
+        ContextEntry::Variable(json!([1, 2, 3, 2, 1])),
+    );
+    let evaluator = Evaluator::new(context);
+    let cases = [
+        ("list.includes(3)", json!(true)),
+        ("list.includes('3')", json!(false)),
+        ("list.includes(1, 1)", json!(true)),
+        ("list.includes(3, -2)", json!(false)),
+        ("[NaN].includes(NaN)", json!(true)),
+        ("[NaN].indexOf(NaN)", json!(-1)),
+        ("list.indexOf(2)", json!(1)),
+        ("list.indexOf(2, 2)", json!(3)),
+        ("list.indexOf(9)", json!(-1)),
+        ("list.lastIndexOf(2)", json!(3)),
+        ("list.lastIndexOf(2, 2)", json!(1)),
+        ("list.lastIndexOf(1, -2)", json!(0)),
+        ("list.slice(1, 3)", json!([2, 3])),
+        ("list.slice(-2)", json!([2, 1])),
+        ("list.slice(3, 1)", json!([])),
+        ("list.slice()", json!([1, 2, 3, 2, 1])),
+        ("[1].concat(2, [3, [4]])", json!([1, 2, 3, [4]])),
+        ("[1, 2, 3].reverse()", json!([3, 2, 1])),
+        ("[1, [2, [3, [4]]]].flat()", json!([1, 2, [3, [4]]])),
+        ("[1, [2, [3, [4]]]].flat(Infinity)", json!([1, 2, 3, 4])),
+        ("list.at(-1)", json!(1)),
+        ("list.at(1.7)", json!(2)),
+        ("list.at(10)", Value::Null),
+        ("[1, 2, 3, 4].fill(0, 1, -1)", json!([1, 0, 0, 4])),
+        ("['a', 'b'].keys()", json!([0, 1])),
+        // The same methods through first-argument dispatch.
+        ("includes(list, 2)", json!(true)),
+        ("slice(list, 1, 2)", json!([2])),
+        ("join(list, '+')", json!("1+2+3+2+1")),
+        ("at(list, -2)", json!(2)),
+    ];
+    for (expression, expected) in cases {
+        assert_eq!(
+            evaluator.evaluate(expression).unwrap(),
+            expected,
+            "{}",
+            expression
+        );
+    }
+    // `reverse` and `fill` modify the array they are called on.
+    assert_eq!(
+        evaluator
+            .evaluate("list.reverse() === list && list[0] == 1 && list.fill(7)[4]")
+            .unwrap(),
+        7
+    );
+}
+
+#[test]
+fn test_flat_circular() {
+    use oxide_eval::{Evaluator, EvaluatorError};
+    use serde_json::json;
+
+    let evaluator = Evaluator::new(Default::default());
+    // The same array twice is fine as long as it doesn't contain itself.
+    assert_eq!(
+        evaluator
+            .evaluate_script("const a = [1]; [a, [a]].flat(Infinity)")
+            .unwrap(),
+        json!([1, 1])
+    );
+    for script in [
+        "const a = [1]; a[1] = a; a.flat(Infinity)",
+        "const a = [1]; a[1] = [[a]]; [a].flat(Infinity)",
+    ] {
+        match evaluator.evaluate_script(script).unwrap_err() {
+            EvaluatorError::TypeError { message, .. } => {
+                assert_eq!(message, "Cannot flatten an array that contains itself");
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
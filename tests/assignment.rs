@@ -0,0 +1,246 @@
+use oxide_eval::{context::ContextEntry, Evaluation, Evaluator, EvaluatorError};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+fn evaluator() -> Evaluator {
+    let mut context = HashMap::new();
+    context.insert("rate".to_string(), ContextEntry::Variable(json!(2)));
+    Evaluator::new(context)
+}
+
+fn variables(value: Value) -> HashMap<String, Value> {
+    serde_json::from_value(value).unwrap()
+}
+
+/// Evaluates `expression` with `x`, `name`, `nothing`, `order` and `list`.
+fn run(expression: &str) -> Evaluation {
+    let initial = variables(json!({
+        "x": 1,
+        "name": "Ada",
+        "nothing": null,
+        "order": { "count": 1 },
+        "list": [1, 2],
+    }));
+    evaluator()
+        .evaluate_mut(expression, initial)
+        .unwrap_or_else(|e| panic!("{}: {:?}", expression, e))
+}
+
+#[test]
+fn test_assignment() {
+    let cases = [
+        ("x = 5", json!(5), "x", json!(5)),
+        ("x += 2", json!(3), "x", json!(3)),
+        ("(x -= 3, x *= 4)", json!(-8), "x", json!(-8)),
+        ("x **= 3", json!(1), "x", json!(1)),
+        ("x <<= 4", json!(16), "x", json!(16)),
+        ("name += '!'", json!("Ada!"), "name", json!("Ada!")),
+        ("x = name = 'b'", json!("b"), "x", json!("b")),
+        ("total = x + 1", json!(2), "total", json!(2)),
+        ("(total = 1, total += 1)", json!(2), "total", json!(2)),
+    ];
+    for (expression, value, name, variable) in cases {
+        let result = run(expression);
+        assert_eq!(result.value, value, "{}", expression);
+        assert_eq!(result.variables[name], variable, "{}", expression);
+    }
+    let result = run("x = 5");
+    assert_eq!(result.variables["name"], "Ada");
+    assert_eq!(result.variables.len(), 5);
+}
+
+#[test]
+fn test_logical_assignment() {
+    let cases = [
+        ("nothing ??= 7", json!(7), "nothing", json!(7)),
+        ("x ??= missing", json!(1), "x", json!(1)),
+        ("x ||= missing", json!(1), "x", json!(1)),
+        ("nothing ||= 'd'", json!("d"), "nothing", json!("d")),
+        ("x &&= name", json!("Ada"), "x", json!("Ada")),
+        ("nothing &&= missing", Value::Null, "nothing", Value::Null),
+        (
+            "order.total ??= 0",
+            json!(0),
+            "order",
+            json!({ "count": 1, "total": 0 }),
+        ),
+    ];
+    for (expression, value, name, variable) in cases {
+        let result = run(expression);
+        assert_eq!(result.value, value, "{}", expression);
+        assert_eq!(result.variables[name], variable, "{}", expression);
+    }
+}
+
+#[test]
+fn test_update() {
+    let cases = [
+        ("x++", json!(1), "x", json!(2)),
+        ("++x", json!(2), "x", json!(2)),
+        ("x--", json!(1), "x", json!(0)),
+        ("(x++, x++, x)", json!(3), "x", json!(3)),
+        ("--order.count", json!(0), "order", json!({ "count": 0 })),
+        ("list[1]++", json!(2), "list", json!([1, 3])),
+        ("(name = '5', name++)", json!(5), "name", json!(6)),
+    ];
+    for (expression, value, name, variable) in cases {
+        let result = run(expression);
+        assert_eq!(result.value, value, "{}", expression);
+        assert_eq!(result.variables[name], variable, "{}", expression);
+    }
+}
+
+#[test]
+fn test_member_assignment() {
+    let cases = [
+        ("order.count += 1", json!({ "count": 2 })),
+        ("order['count'] = 'many'", json!({ "count": "many" })),
+        ("order.items = list", json!({ "count": 1, "items": [1, 2] })),
+    ];
+    for (expression, expected) in cases {
+        assert_eq!(
+            run(expression).variables["order"],
+            expected,
+            "{}",
+            expression
+        );
+    }
+    let cases = [
+        ("list[0] = 9", json!([9, 2])),
+        ("list[-1] = 0", json!([1, 0])),
+        ("list[3] = 4", json!([1, 2, null, 4])),
+        ("list.length = 1", json!([1])),
+        ("list.length = 3", json!([1, 2, null])),
+        ("list.total = 3", json!([1, 2])),
+    ];
+    for (expression, expected) in cases {
+        assert_eq!(
+            run(expression).variables["list"],
+            expected,
+            "{}",
+            expression
+        );
+    }
+    assert_eq!(run("(list.total = 3, list.total)").value, 3);
+    // Objects are shared, so assigning through another reference shows too.
+    assert_eq!(
+        run("(copy = order, copy.count = 5)").variables["order"],
+        json!({ "count": 5 })
+    );
+}
+
+#[test]
+fn test_context_is_not_changed() {
+    let evaluator = evaluator();
+    let result = evaluator.evaluate_mut("rate *= 3", HashMap::new()).unwrap();
+    assert_eq!(result.value, 6);
+    assert_eq!(result.variables, variables(json!({ "rate": 6 })));
+    assert_eq!(evaluator.evaluate("rate").unwrap(), 2);
+}
+
+#[test]
+fn test_compiled_assignment() {
+    let evaluator = evaluator();
+    let compiled = Evaluator::compile("count = count + rate").unwrap();
+    let mut state = variables(json!({ "count": 0 }));
+    for _ in 0..3 {
+        state = evaluator
+            .evaluate_compiled_mut(&compiled, state)
+            .unwrap()
+            .variables;
+    }
+    assert_eq!(state["count"], 6);
+}
+
+#[test]
+fn test_assignment_to_locals() {
+    let evaluator = evaluator();
+    assert_eq!(
+        evaluator
+            .evaluate("((strings, n) => (n += rate, n * 10))`${1}`")
+            .unwrap(),
+        30
+    );
+    // Function values assign to the variables of a mutable scope.
+    let result = evaluator
+        .evaluate_mut("((strings, n) => total = n)`${4}`", HashMap::new())
+        .unwrap();
+    assert_eq!(result.variables["total"], 4);
+}
+
+#[test]
+fn test_assignment_errors() {
+    let evaluator = evaluator();
+    match evaluator.evaluate("1 + (rate = 3)").unwrap_err() {
+        EvaluatorError::UnsupportedSyntax { span, .. } => {
+            assert_eq!((span.start, span.end), (5, 13));
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+    let initial = variables(json!({ "nothing": null, "name": "Ada", "list": [1] }));
+    let cases = [
+        "missing += 1",
+        "missing++",
+        "nothing.x = 1",
+        "nothing.x += 1",
+        "name.x = 1",
+        "list[-2] = 1",
+        "list.length = -1",
+        "list[1e9] = 1",
+        "[a] = list",
+    ];
+    for expression in cases {
+        let error = evaluator
+            .evaluate_mut(expression, initial.clone())
+            .unwrap_err();
+        let expected = match expression {
+            "missing += 1" | "missing++" => {
+                matches!(error, EvaluatorError::VariableNotFound { .. })
+            }
+            "list[-2] = 1" => matches!(error, EvaluatorError::IndexOutOfRange { .. }),
+            "[a] = list" => matches!(error, EvaluatorError::UnsupportedSyntax { .. }),
+            _ => matches!(error, EvaluatorError::TypeError { .. }),
+        };
+        assert!(expected, "{}: {:?}", expression, error);
+    }
+}
+
+#[test]
+fn test_circular_structure() {
+    let evaluator = evaluator();
+    match evaluator
+        .evaluate_mut("(order.self = order, 1)", variables(json!({ "order": {} })))
+        .unwrap_err()
+    {
+        EvaluatorError::TypeError { message, .. } => {
+            assert_eq!(message, "Converting circular structure to JSON");
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+    let cases = [
+        (
+            "const list = [1, 2]; list[1] = list; list + ''",
+            json!("1,"),
+        ),
+        (
+            "const list = [1, 2]; list[1] = [list, 3]; `${list}`",
+            json!("1,,3"),
+        ),
+        // The same array twice is fine as long as it doesn't contain itself.
+        ("const shared = [0]; [shared, shared]", json!([[0], [0]])),
+    ];
+    for (script, expected) in cases {
+        assert_eq!(
+            evaluator.evaluate_script(script).unwrap(),
+            expected,
+            "{}",
+            script
+        );
+    }
+    assert!(matches!(
+        evaluator
+            .evaluate_script("const list = [1]; list[1] = { list }; list")
+            .unwrap_err(),
+        EvaluatorError::TypeError { .. }
+    ));
+}
@@ -0,0 +1,280 @@
+#[test]
+fn test_compare_numbers() {
+    use std::collections::HashMap;
+
+    use oxide_eval::Evaluator;
+
+    let context = HashMap::new();
+    let evaluator = Evaluator::new(context);
+    // Numbers compare numerically, not as strings.
+    assert_eq!(evaluator.evaluate("10 < 9").unwrap(), false);
+    assert_eq!(evaluator.evaluate("10 <= 9").unwrap(), false);
+    assert_eq!(evaluator.evaluate("10 > 9").unwrap(), true);
+    assert_eq!(evaluator.evaluate("10 >= 9").unwrap(), true);
+    assert_eq!(evaluator.evaluate("100 < 20").unwrap(), false);
+    assert_eq!(evaluator.evaluate("100 <= 20").unwrap(), false);
+    assert_eq!(evaluator.evaluate("100 > 20").unwrap(), true);
+    assert_eq!(evaluator.evaluate("100 >= 20").unwrap(), true);
+    assert_eq!(evaluator.evaluate("-1 < -2").unwrap(), false);
+    assert_eq!(evaluator.evaluate("-1 <= -2").unwrap(), false);
+    assert_eq!(evaluator.evaluate("-1 > -2").unwrap(), true);
+    assert_eq!(evaluator.evaluate("-1 >= -2").unwrap(), true);
+    assert_eq!(evaluator.evaluate("1.5 < 1.5").unwrap(), false);
+    assert_eq!(evaluator.evaluate("1.5 <= 1.5").unwrap(), true);
+    assert_eq!(evaluator.evaluate("1.5 > 1.5").unwrap(), false);
+    assert_eq!(evaluator.evaluate("1.5 >= 1.5").unwrap(), true);
+    assert_eq!(evaluator.evaluate("0 < -0").unwrap(), false);
+    assert_eq!(evaluator.evaluate("0 <= -0").unwrap(), true);
+    assert_eq!(evaluator.evaluate("0 > -0").unwrap(), false);
+    assert_eq!(evaluator.evaluate("0 >= -0").unwrap(), true);
+    assert_eq!(evaluator.evaluate("Infinity < 1e308").unwrap(), false);
+    assert_eq!(evaluator.evaluate("Infinity <= 1e308").unwrap(), false);
+    assert_eq!(evaluator.evaluate("Infinity > 1e308").unwrap(), true);
+    assert_eq!(evaluator.evaluate("Infinity >= 1e308").unwrap(), true);
+    assert_eq!(evaluator.evaluate("-Infinity < -1e308").unwrap(), true);
+    assert_eq!(evaluator.evaluate("-Infinity <= -1e308").unwrap(), true);
+    assert_eq!(evaluator.evaluate("-Infinity > -1e308").unwrap(), false);
+    assert_eq!(evaluator.evaluate("-Infinity >= -1e308").unwrap(), false);
+}
+
+#[test]
+fn test_compare_strings() {
+    use std::collections::HashMap;
+
+    use oxide_eval::Evaluator;
+
+    let context = HashMap::new();
+    let evaluator = Evaluator::new(context);
+    // Strings compare by UTF-16 code units.
+    assert_eq!(evaluator.evaluate("'10' < '9'").unwrap(), true);
+    assert_eq!(evaluator.evaluate("'10' <= '9'").unwrap(), true);
+    assert_eq!(evaluator.evaluate("'10' > '9'").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'10' >= '9'").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'a' < 'b'").unwrap(), true);
+    assert_eq!(evaluator.evaluate("'a' <= 'b'").unwrap(), true);
+    assert_eq!(evaluator.evaluate("'a' > 'b'").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'a' >= 'b'").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'B' < 'a'").unwrap(), true);
+    assert_eq!(evaluator.evaluate("'B' <= 'a'").unwrap(), true);
+    assert_eq!(evaluator.evaluate("'B' > 'a'").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'B' >= 'a'").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'abc' < 'ab'").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'abc' <= 'ab'").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'abc' > 'ab'").unwrap(), true);
+    assert_eq!(evaluator.evaluate("'abc' >= 'ab'").unwrap(), true);
+    assert_eq!(evaluator.evaluate("'' < ''").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'' <= ''").unwrap(), true);
+    assert_eq!(evaluator.evaluate("'' > ''").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'' >= ''").unwrap(), true);
+    assert_eq!(
+        evaluator.evaluate("'\u{ff61}' < '\u{1f600}'").unwrap(),
+        false
+    );
+    assert_eq!(
+        evaluator.evaluate("'\u{ff61}' <= '\u{1f600}'").unwrap(),
+        false
+    );
+    assert_eq!(
+        evaluator.evaluate("'\u{ff61}' > '\u{1f600}'").unwrap(),
+        true
+    );
+    assert_eq!(
+        evaluator.evaluate("'\u{ff61}' >= '\u{1f600}'").unwrap(),
+        true
+    );
+}
+
+#[test]
+fn test_compare_mixed_types() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::Value;
+
+    let mut context = HashMap::new();
+    context.insert("n".to_string(), ContextEntry::Variable(Value::Null));
+    let evaluator = Evaluator::new(context);
+    // A number on either side makes the comparison numeric, and booleans and
+    // null become numbers.
+    assert_eq!(evaluator.evaluate("'10' < 9").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'10' <= 9").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'10' > 9").unwrap(), true);
+    assert_eq!(evaluator.evaluate("'10' >= 9").unwrap(), true);
+    assert_eq!(evaluator.evaluate("2 < '10'").unwrap(), true);
+    assert_eq!(evaluator.evaluate("2 <= '10'").unwrap(), true);
+    assert_eq!(evaluator.evaluate("2 > '10'").unwrap(), false);
+    assert_eq!(evaluator.evaluate("2 >= '10'").unwrap(), false);
+    assert_eq!(evaluator.evaluate("' 3 ' < 3").unwrap(), false);
+    assert_eq!(evaluator.evaluate("' 3 ' <= 3").unwrap(), true);
+    assert_eq!(evaluator.evaluate("' 3 ' > 3").unwrap(), false);
+    assert_eq!(evaluator.evaluate("' 3 ' >= 3").unwrap(), true);
+    assert_eq!(evaluator.evaluate("'' < 0").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'' <= 0").unwrap(), true);
+    assert_eq!(evaluator.evaluate("'' > 0").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'' >= 0").unwrap(), true);
+    assert_eq!(evaluator.evaluate("'0x10' < 15").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'0x10' <= 15").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'0x10' > 15").unwrap(), true);
+    assert_eq!(evaluator.evaluate("'0x10' >= 15").unwrap(), true);
+    assert_eq!(evaluator.evaluate("true < false").unwrap(), false);
+    assert_eq!(evaluator.evaluate("true <= false").unwrap(), false);
+    assert_eq!(evaluator.evaluate("true > false").unwrap(), true);
+    assert_eq!(evaluator.evaluate("true >= false").unwrap(), true);
+    assert_eq!(evaluator.evaluate("true < 1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("true <= 1").unwrap(), true);
+    assert_eq!(evaluator.evaluate("true > 1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("true >= 1").unwrap(), true);
+    assert_eq!(evaluator.evaluate("false < 0.5").unwrap(), true);
+    assert_eq!(evaluator.evaluate("false <= 0.5").unwrap(), true);
+    assert_eq!(evaluator.evaluate("false > 0.5").unwrap(), false);
+    assert_eq!(evaluator.evaluate("false >= 0.5").unwrap(), false);
+    assert_eq!(evaluator.evaluate("null < 0").unwrap(), false);
+    assert_eq!(evaluator.evaluate("null <= 0").unwrap(), true);
+    assert_eq!(evaluator.evaluate("null > 0").unwrap(), false);
+    assert_eq!(evaluator.evaluate("null >= 0").unwrap(), true);
+    assert_eq!(evaluator.evaluate("n < 1").unwrap(), true);
+    assert_eq!(evaluator.evaluate("n <= 1").unwrap(), true);
+    assert_eq!(evaluator.evaluate("n > 1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("n >= 1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("null < -1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("null <= -1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("null > -1").unwrap(), true);
+    assert_eq!(evaluator.evaluate("null >= -1").unwrap(), true);
+}
+
+#[test]
+fn test_compare_nan() {
+    use std::collections::HashMap;
+
+    use oxide_eval::Evaluator;
+
+    let context = HashMap::new();
+    let evaluator = Evaluator::new(context);
+    // NaN makes every comparison false.
+    assert_eq!(evaluator.evaluate("NaN < NaN").unwrap(), false);
+    assert_eq!(evaluator.evaluate("NaN <= NaN").unwrap(), false);
+    assert_eq!(evaluator.evaluate("NaN > NaN").unwrap(), false);
+    assert_eq!(evaluator.evaluate("NaN >= NaN").unwrap(), false);
+    assert_eq!(evaluator.evaluate("NaN < 1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("NaN <= 1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("NaN > 1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("NaN >= 1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'abc' < 1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'abc' <= 1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'abc' > 1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'abc' >= 1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("undefined < 0").unwrap(), false);
+    assert_eq!(evaluator.evaluate("undefined <= 0").unwrap(), false);
+    assert_eq!(evaluator.evaluate("undefined > 0").unwrap(), false);
+    assert_eq!(evaluator.evaluate("undefined >= 0").unwrap(), false);
+    assert_eq!(evaluator.evaluate("undefined < undefined").unwrap(), false);
+    assert_eq!(evaluator.evaluate("undefined <= undefined").unwrap(), false);
+    assert_eq!(evaluator.evaluate("undefined > undefined").unwrap(), false);
+    assert_eq!(evaluator.evaluate("undefined >= undefined").unwrap(), false);
+    assert_eq!(evaluator.evaluate("null < undefined").unwrap(), false);
+    assert_eq!(evaluator.evaluate("null <= undefined").unwrap(), false);
+    assert_eq!(evaluator.evaluate("null > undefined").unwrap(), false);
+    assert_eq!(evaluator.evaluate("null >= undefined").unwrap(), false);
+}
+
+#[test]
+fn test_compare_arrays_and_objects() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert("list".to_string(), ContextEntry::Variable(json!([2])));
+    context.insert("pair".to_string(), ContextEntry::Variable(json!([1, 2])));
+    context.insert("obj".to_string(), ContextEntry::Variable(json!({ "a": 1 })));
+    let evaluator = Evaluator::new(context);
+    // Arrays and objects are converted to primitives first.
+    assert_eq!(evaluator.evaluate("list < 3").unwrap(), true);
+    assert_eq!(evaluator.evaluate("list <= 3").unwrap(), true);
+    assert_eq!(evaluator.evaluate("list > 3").unwrap(), false);
+    assert_eq!(evaluator.evaluate("list >= 3").unwrap(), false);
+    assert_eq!(evaluator.evaluate("[10] < 9").unwrap(), false);
+    assert_eq!(evaluator.evaluate("[10] <= 9").unwrap(), false);
+    assert_eq!(evaluator.evaluate("[10] > 9").unwrap(), true);
+    assert_eq!(evaluator.evaluate("[10] >= 9").unwrap(), true);
+    assert_eq!(evaluator.evaluate("[] < 0").unwrap(), false);
+    assert_eq!(evaluator.evaluate("[] <= 0").unwrap(), true);
+    assert_eq!(evaluator.evaluate("[] > 0").unwrap(), false);
+    assert_eq!(evaluator.evaluate("[] >= 0").unwrap(), true);
+    assert_eq!(evaluator.evaluate("[] < ''").unwrap(), false);
+    assert_eq!(evaluator.evaluate("[] <= ''").unwrap(), true);
+    assert_eq!(evaluator.evaluate("[] > ''").unwrap(), false);
+    assert_eq!(evaluator.evaluate("[] >= ''").unwrap(), true);
+    assert_eq!(evaluator.evaluate("pair < '1,2'").unwrap(), false);
+    assert_eq!(evaluator.evaluate("pair <= '1,2'").unwrap(), true);
+    assert_eq!(evaluator.evaluate("pair > '1,2'").unwrap(), false);
+    assert_eq!(evaluator.evaluate("pair >= '1,2'").unwrap(), true);
+    assert_eq!(evaluator.evaluate("pair < 1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("pair <= 1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("pair > 1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("pair >= 1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("['b'] < ['a']").unwrap(), false);
+    assert_eq!(evaluator.evaluate("['b'] <= ['a']").unwrap(), false);
+    assert_eq!(evaluator.evaluate("['b'] > ['a']").unwrap(), true);
+    assert_eq!(evaluator.evaluate("['b'] >= ['a']").unwrap(), true);
+    assert_eq!(evaluator.evaluate("['t'] < ['abacv']").unwrap(), false);
+    assert_eq!(evaluator.evaluate("['t'] <= ['abacv']").unwrap(), false);
+    assert_eq!(evaluator.evaluate("['t'] > ['abacv']").unwrap(), true);
+    assert_eq!(evaluator.evaluate("['t'] >= ['abacv']").unwrap(), true);
+    assert_eq!(
+        evaluator.evaluate("obj < '[object Object]'").unwrap(),
+        false
+    );
+    assert_eq!(
+        evaluator.evaluate("obj <= '[object Object]'").unwrap(),
+        true
+    );
+    assert_eq!(
+        evaluator.evaluate("obj > '[object Object]'").unwrap(),
+        false
+    );
+    assert_eq!(
+        evaluator.evaluate("obj >= '[object Object]'").unwrap(),
+        true
+    );
+    assert_eq!(evaluator.evaluate("obj < 1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("obj <= 1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("obj > 1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("obj >= 1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("['abacv'] < obj").unwrap(), false);
+    assert_eq!(evaluator.evaluate("['abacv'] <= obj").unwrap(), false);
+    assert_eq!(evaluator.evaluate("['abacv'] > obj").unwrap(), true);
+    assert_eq!(evaluator.evaluate("['abacv'] >= obj").unwrap(), true);
+}
+
+#[test]
+fn test_comparison_chains_with_numbers() {
+    use std::collections::HashMap;
+
+    use oxide_eval::Evaluator;
+
+    let evaluator = Evaluator::new(HashMap::new());
+    assert_eq!(evaluator.evaluate("2 < 10 && 10 < 100").unwrap(), true);
+    // `<` is left-associative, so this compares `true` with 1.
+    assert_eq!(evaluator.evaluate("3 > 2 > 1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("1 < 2 < 3").unwrap(), true);
+}
+
+#[test]
+fn test_compare_errors() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{Evaluator, EvaluatorError};
+
+    let evaluator = Evaluator::new(HashMap::new());
+    let err = evaluator.evaluate("1 < missing").unwrap_err();
+    match err {
+        EvaluatorError::VariableNotFound { name, .. } => assert_eq!(name, "missing"),
+        _ => panic!("unexpected error: {:?}", err),
+    }
+    assert!(matches!(
+        evaluator.evaluate("missing >= 1").unwrap_err(),
+        EvaluatorError::VariableNotFound { .. }
+    ));
+}
@@ -0,0 +1,66 @@
+use oxide_eval::{context::ContextEntry, Evaluator};
+use serde_json::json;
+use std::collections::HashMap;
+
+#[test]
+fn test_compile_reuse() {
+    let compiled = Evaluator::compile("a * 2 + (b ? 1 : 0)").unwrap();
+
+    for i in 0..10 {
+        let mut context = HashMap::new();
+        context.insert("a".to_string(), ContextEntry::Variable(json!(i)));
+        context.insert("b".to_string(), ContextEntry::Variable(json!(i % 2 == 0)));
+        let evaluator = Evaluator::new(context);
+        let expected = i * 2 + if i % 2 == 0 { 1 } else { 0 };
+        assert_eq!(evaluator.evaluate_compiled(&compiled).unwrap(), expected);
+    }
+}
+
+#[test]
+fn test_compile_matches_evaluate() {
+    let mut context = HashMap::new();
+    context.insert(
+        "c".to_string(),
+        ContextEntry::Variable(json!({ "b": { "a": 32 } })),
+    );
+    context.insert(
+        "mul".to_string(),
+        ContextEntry::Function(Box::new(|args| json!(args[0].as_f64().unwrap() * 10.0))),
+    );
+    let evaluator = Evaluator::new(context);
+
+    for expression in [
+        "c.b.a + mul(2)",
+        "c.b?.d",
+        "settings?.ok",
+        "\"b\"+\"a\"+ +\"a\"+\"a\"",
+        "'directive'",
+        "[1, 2, 3]",
+        "-[[[[[[\"4.5\"]]]]]]",
+    ] {
+        let compiled = Evaluator::compile(expression).unwrap();
+        assert_eq!(compiled.source(), expression);
+        assert_eq!(
+            evaluator.evaluate_compiled(&compiled).unwrap(),
+            evaluator.evaluate(expression).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_compile_errors() {
+    assert!(Evaluator::compile("1 +").is_err());
+    assert!(Evaluator::compile("").is_err());
+
+    // Unsupported syntax is reported when it is reached, not when compiling.
+    let compiled = Evaluator::compile("true ? 1 : this").unwrap();
+    let evaluator = Evaluator::new(HashMap::new());
+    assert_eq!(evaluator.evaluate_compiled(&compiled).unwrap(), 1);
+
+    let compiled = Evaluator::compile("false ? 1 : this").unwrap();
+    assert!(evaluator.evaluate_compiled(&compiled).is_err());
+    assert_eq!(
+        evaluator.evaluate("missing").unwrap_err().to_string(),
+        "Variable not found: missing"
+    );
+}
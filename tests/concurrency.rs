@@ -0,0 +1,59 @@
+use oxide_eval::{context::ContextEntry, CompiledExpression, Evaluator, EvaluatorError};
+use serde_json::json;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn test_send_sync() {
+    assert_send_sync::<Evaluator>();
+    assert_send_sync::<ContextEntry>();
+    assert_send_sync::<CompiledExpression>();
+    assert_send_sync::<EvaluatorError>();
+}
+
+#[test]
+fn test_concurrent_evaluate() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+
+    let mut context = HashMap::new();
+    context.insert("base".to_string(), ContextEntry::Variable(json!(10)));
+    context.insert(
+        "double".to_string(),
+        ContextEntry::Function(Box::new(move |args| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            json!(args[0].as_f64().unwrap() * 2.0)
+        })),
+    );
+    let evaluator = Arc::new(Evaluator::new(context));
+    let compiled = Arc::new(Evaluator::compile("double(base) + 1").unwrap());
+
+    let handles = (0..16)
+        .map(|i| {
+            let evaluator = evaluator.clone();
+            let compiled = compiled.clone();
+            thread::spawn(move || {
+                for _ in 0..100 {
+                    assert_eq!(evaluator.evaluate_compiled(&compiled).unwrap(), 21.0);
+                    assert_eq!(
+                        evaluator.evaluate(&format!("base + {}", i)).unwrap(),
+                        10 + i
+                    );
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(calls.load(Ordering::SeqCst), 16 * 100);
+}
@@ -0,0 +1,47 @@
+use oxide_eval::{context::ContextEntry, Evaluator};
+use serde_json::json;
+use std::collections::HashMap;
+
+#[test]
+fn test_runtime_diagnostic() {
+    let mut context = HashMap::new();
+    context.insert("a".to_string(), ContextEntry::Variable(json!(1)));
+    let evaluator = Evaluator::new(context);
+
+    let diagnostics = evaluator.diagnose("a +\n  missing * 2").unwrap_err();
+    assert_eq!(diagnostics.len(), 1);
+    let diagnostic = &diagnostics[0];
+    assert_eq!(diagnostic.line, 2);
+    assert_eq!(diagnostic.column, 3);
+    let expected = [
+        "error: Variable not found: missing",
+        "  --> 2:3",
+        "  |",
+        "2 |   missing * 2",
+        "  |   ^^^^^^^",
+        "  = help: add the variable to the context, or use `?.` if it is optional",
+    ]
+    .join("\n");
+    assert_eq!(diagnostic.render(), expected);
+}
+
+#[test]
+fn test_parse_diagnostics() {
+    let source = "(1 + ) + (2 * )";
+    let err = Evaluator::compile(source).unwrap_err();
+    let diagnostics = err.diagnostics(source);
+    assert!(!diagnostics.is_empty());
+    for diagnostic in &diagnostics {
+        assert_eq!(diagnostic.line, 1);
+        assert!(diagnostic.render().contains("1 | (1 + ) + (2 * )"));
+    }
+    assert_eq!(diagnostics[0].column, 6);
+}
+
+#[test]
+fn test_multibyte_column() {
+    let evaluator = Evaluator::new(HashMap::new());
+    let diagnostics = evaluator.diagnose("'héllo' + nope").unwrap_err();
+    assert_eq!(diagnostics[0].column, 11);
+    assert!(diagnostics[0].render().contains("\n  |           ^^^^"));
+}
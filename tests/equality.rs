@@ -0,0 +1,216 @@
+#[test]
+fn test_equality_null_and_undefined() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::Value;
+
+    let mut context = HashMap::new();
+    context.insert("n".to_string(), ContextEntry::Variable(Value::Null));
+    let evaluator = Evaluator::new(context);
+    // `null` and `undefined` only loosely equal each other.
+    assert_eq!(evaluator.evaluate("null == undefined").unwrap(), true);
+    assert_eq!(evaluator.evaluate("null === undefined").unwrap(), false);
+    assert_eq!(evaluator.evaluate("n == undefined").unwrap(), true);
+    assert_eq!(evaluator.evaluate("n === undefined").unwrap(), false);
+    assert_eq!(evaluator.evaluate("null == null").unwrap(), true);
+    assert_eq!(evaluator.evaluate("null === null").unwrap(), true);
+    assert_eq!(evaluator.evaluate("undefined == undefined").unwrap(), true);
+    assert_eq!(evaluator.evaluate("undefined === undefined").unwrap(), true);
+    assert_eq!(evaluator.evaluate("null == 0").unwrap(), false);
+    assert_eq!(evaluator.evaluate("null === 0").unwrap(), false);
+    assert_eq!(evaluator.evaluate("undefined == 0").unwrap(), false);
+    assert_eq!(evaluator.evaluate("undefined === 0").unwrap(), false);
+    assert_eq!(evaluator.evaluate("null == false").unwrap(), false);
+    assert_eq!(evaluator.evaluate("null === false").unwrap(), false);
+    assert_eq!(evaluator.evaluate("null == ''").unwrap(), false);
+    assert_eq!(evaluator.evaluate("null === ''").unwrap(), false);
+}
+
+#[test]
+fn test_equality_primitives() {
+    use std::collections::HashMap;
+
+    use oxide_eval::Evaluator;
+
+    let evaluator = Evaluator::new(HashMap::new());
+    // Loose equality converts booleans and strings to numbers.
+    assert_eq!(evaluator.evaluate("true == 1").unwrap(), true);
+    assert_eq!(evaluator.evaluate("true === 1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("false == 0").unwrap(), true);
+    assert_eq!(evaluator.evaluate("false === 0").unwrap(), false);
+    assert_eq!(evaluator.evaluate("true == '1'").unwrap(), true);
+    assert_eq!(evaluator.evaluate("true === '1'").unwrap(), false);
+    assert_eq!(evaluator.evaluate("true == 2").unwrap(), false);
+    assert_eq!(evaluator.evaluate("true === 2").unwrap(), false);
+    assert_eq!(evaluator.evaluate("false == ''").unwrap(), true);
+    assert_eq!(evaluator.evaluate("false === ''").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'' == 0").unwrap(), true);
+    assert_eq!(evaluator.evaluate("'' === 0").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'0' == 0").unwrap(), true);
+    assert_eq!(evaluator.evaluate("'0' === 0").unwrap(), false);
+    assert_eq!(evaluator.evaluate("' 1 ' == 1").unwrap(), true);
+    assert_eq!(evaluator.evaluate("' 1 ' === 1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'1e3' == 1000").unwrap(), true);
+    assert_eq!(evaluator.evaluate("'1e3' === 1000").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'abc' == NaN").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'abc' === NaN").unwrap(), false);
+    assert_eq!(evaluator.evaluate("NaN == NaN").unwrap(), false);
+    assert_eq!(evaluator.evaluate("NaN === NaN").unwrap(), false);
+    assert_eq!(evaluator.evaluate("0 == -0").unwrap(), true);
+    assert_eq!(evaluator.evaluate("0 === -0").unwrap(), true);
+    assert_eq!(evaluator.evaluate("'a' == 'a'").unwrap(), true);
+    assert_eq!(evaluator.evaluate("'a' === 'a'").unwrap(), true);
+    assert_eq!(evaluator.evaluate("'1' == '01'").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'1' === '01'").unwrap(), false);
+}
+
+#[test]
+fn test_equality_objects_with_primitives() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert("o".to_string(), ContextEntry::Variable(json!({ "x": 1 })));
+    let evaluator = Evaluator::new(context);
+    // An object compared with a primitive is converted to a primitive first.
+    assert_eq!(evaluator.evaluate("[1] == 1").unwrap(), true);
+    assert_eq!(evaluator.evaluate("[1] === 1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("[1] == '1'").unwrap(), true);
+    assert_eq!(evaluator.evaluate("[1] === '1'").unwrap(), false);
+    assert_eq!(evaluator.evaluate("[1] == true").unwrap(), true);
+    assert_eq!(evaluator.evaluate("[1] === true").unwrap(), false);
+    assert_eq!(evaluator.evaluate("[] == 0").unwrap(), true);
+    assert_eq!(evaluator.evaluate("[] === 0").unwrap(), false);
+    assert_eq!(evaluator.evaluate("[] == ''").unwrap(), true);
+    assert_eq!(evaluator.evaluate("[] === ''").unwrap(), false);
+    assert_eq!(evaluator.evaluate("[] == false").unwrap(), true);
+    assert_eq!(evaluator.evaluate("[] === false").unwrap(), false);
+    assert_eq!(evaluator.evaluate("[0] == false").unwrap(), true);
+    assert_eq!(evaluator.evaluate("[0] === false").unwrap(), false);
+    assert_eq!(evaluator.evaluate("[1, 2] == '1,2'").unwrap(), true);
+    assert_eq!(evaluator.evaluate("[1, 2] === '1,2'").unwrap(), false);
+    assert_eq!(evaluator.evaluate("[null] == ''").unwrap(), true);
+    assert_eq!(evaluator.evaluate("[null] === ''").unwrap(), false);
+    assert_eq!(evaluator.evaluate("[] == null").unwrap(), false);
+    assert_eq!(evaluator.evaluate("[] === null").unwrap(), false);
+    assert_eq!(evaluator.evaluate("o == '[object Object]'").unwrap(), true);
+    assert_eq!(
+        evaluator.evaluate("o === '[object Object]'").unwrap(),
+        false
+    );
+}
+
+#[test]
+fn test_equality_references() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert("a".to_string(), ContextEntry::Variable(json!([1])));
+    context.insert("b".to_string(), ContextEntry::Variable(json!([1])));
+    context.insert("o".to_string(), ContextEntry::Variable(json!({ "x": 1 })));
+    context.insert(
+        "nested".to_string(),
+        ContextEntry::Variable(json!({ "inner": { "x": 1 } })),
+    );
+    let evaluator = Evaluator::new(context);
+    // Two objects are only equal when they are the same object.
+    assert_eq!(evaluator.evaluate("[1] == [1]").unwrap(), false);
+    assert_eq!(evaluator.evaluate("[1] === [1]").unwrap(), false);
+    assert_eq!(evaluator.evaluate("({}) == ({})").unwrap(), false);
+    assert_eq!(evaluator.evaluate("({}) === ({})").unwrap(), false);
+    assert_eq!(evaluator.evaluate("a == a").unwrap(), true);
+    assert_eq!(evaluator.evaluate("a === a").unwrap(), true);
+    assert_eq!(evaluator.evaluate("a == b").unwrap(), false);
+    assert_eq!(evaluator.evaluate("a === b").unwrap(), false);
+    assert_eq!(evaluator.evaluate("o == o").unwrap(), true);
+    assert_eq!(evaluator.evaluate("o === o").unwrap(), true);
+    assert_eq!(
+        evaluator.evaluate("nested.inner == nested.inner").unwrap(),
+        true
+    );
+    assert_eq!(
+        evaluator.evaluate("nested.inner === nested.inner").unwrap(),
+        true
+    );
+    assert_eq!(evaluator.evaluate("a == o").unwrap(), false);
+    assert_eq!(evaluator.evaluate("a === o").unwrap(), false);
+}
+
+#[test]
+fn test_equality_is_symmetric() {
+    use std::collections::HashMap;
+
+    use oxide_eval::Evaluator;
+
+    let evaluator = Evaluator::new(HashMap::new());
+    assert_eq!(evaluator.evaluate("undefined == null").unwrap(), true);
+    assert_eq!(evaluator.evaluate("1 == true").unwrap(), true);
+    assert_eq!(evaluator.evaluate("0 == ''").unwrap(), true);
+    assert_eq!(evaluator.evaluate("'1,2' == [1, 2]").unwrap(), true);
+    assert_eq!(evaluator.evaluate("false == [0]").unwrap(), true);
+    assert_eq!(evaluator.evaluate("null == []").unwrap(), false);
+    assert_eq!(evaluator.evaluate("1 === [1]").unwrap(), false);
+}
+
+#[test]
+fn test_inequality() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert("a".to_string(), ContextEntry::Variable(json!([1])));
+    let evaluator = Evaluator::new(context);
+    assert_eq!(evaluator.evaluate("null != undefined").unwrap(), false);
+    assert_eq!(evaluator.evaluate("null !== undefined").unwrap(), true);
+    assert_eq!(evaluator.evaluate("'1' != 1").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'1' !== 1").unwrap(), true);
+    assert_eq!(evaluator.evaluate("NaN != NaN").unwrap(), true);
+    assert_eq!(evaluator.evaluate("NaN !== NaN").unwrap(), true);
+    assert_eq!(evaluator.evaluate("a != a").unwrap(), false);
+    assert_eq!(evaluator.evaluate("a !== a").unwrap(), false);
+    assert_eq!(evaluator.evaluate("[1] != [1]").unwrap(), true);
+    assert_eq!(evaluator.evaluate("[1] !== 1").unwrap(), true);
+}
+
+#[test]
+fn test_identity_is_per_evaluation() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert("a".to_string(), ContextEntry::Variable(json!([1])));
+    context.insert("b".to_string(), ContextEntry::Variable(json!([1])));
+    let evaluator = Evaluator::new(context);
+    assert_eq!(evaluator.evaluate("a === a").unwrap(), true);
+    assert_eq!(evaluator.evaluate("a == b").unwrap(), false);
+    let values = HashMap::from([("a".to_string(), json!({}))]);
+    assert_eq!(evaluator.evaluate_with("a === a", &values).unwrap(), true);
+}
+
+#[test]
+fn test_equality_errors() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{Evaluator, EvaluatorError};
+
+    let evaluator = Evaluator::new(HashMap::new());
+    let err = evaluator.evaluate("missing == null").unwrap_err();
+    match err {
+        EvaluatorError::VariableNotFound { name, .. } => assert_eq!(name, "missing"),
+        _ => panic!("unexpected error: {:?}", err),
+    }
+    assert!(matches!(
+        evaluator.evaluate("1 === missing").unwrap_err(),
+        EvaluatorError::VariableNotFound { .. }
+    ));
+}
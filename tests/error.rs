@@ -0,0 +1,123 @@
+use oxide_eval::{context::ContextEntry, Evaluator, EvaluatorError, Span};
+use serde_json::json;
+use std::collections::HashMap;
+
+fn evaluator() -> Evaluator {
+    let mut context = HashMap::new();
+    context.insert("a".to_string(), ContextEntry::Variable(json!({ "b": 1 })));
+    Evaluator::new(context)
+}
+
+#[test]
+fn test_parse_error() {
+    let err = Evaluator::compile("1 + (2").unwrap_err();
+    match &err {
+        EvaluatorError::Parse { errors, span } => {
+            assert!(!errors.is_empty());
+            assert_eq!(*span, errors[0].span);
+        }
+        _ => panic!("unexpected error: {:?}", err),
+    }
+    assert!(err.to_string().starts_with("Parsing error: "));
+}
+
+#[test]
+fn test_variable_not_found_span() {
+    let err = evaluator().evaluate("1 + missing").unwrap_err();
+    assert!(matches!(
+        err,
+        EvaluatorError::VariableNotFound { ref name, span } if name == "missing" && span == Span::new(4, 11)
+    ));
+}
+
+#[test]
+fn test_property_not_found_span() {
+    let err = evaluator().evaluate("a.b + a.c").unwrap_err();
+    match err {
+        EvaluatorError::PropertyNotFound { property, span, .. } => {
+            assert_eq!(property, "c");
+            assert_eq!(span, Span::new(6, 9));
+        }
+        _ => panic!("unexpected error: {:?}", err),
+    }
+}
+
+#[test]
+fn test_unknown_function_span() {
+    let err = evaluator().evaluate("2 * nope(1)").unwrap_err();
+    match err {
+        EvaluatorError::UnknownFunction { name, span } => {
+            assert_eq!(name, "nope");
+            assert_eq!(span, Span::new(4, 11));
+        }
+        _ => panic!("unexpected error: {:?}", err),
+    }
+}
+
+#[test]
+fn test_unsupported_syntax_span() {
+    let err = evaluator().evaluate("1 + this").unwrap_err();
+    assert!(matches!(err, EvaluatorError::UnsupportedSyntax { .. }));
+    assert_eq!(err.span(), Span::new(4, 8));
+}
+
+#[cfg(feature = "string")]
+#[test]
+fn test_argument_errors() {
+    let err = evaluator().evaluate("'abc'.startsWith()").unwrap_err();
+    match err {
+        EvaluatorError::InvalidArgumentCount {
+            function,
+            expected,
+            found,
+            span,
+        } => {
+            assert_eq!(function, "startsWith");
+            assert_eq!(expected, "1");
+            assert_eq!(found, 0);
+            assert_eq!(span, Span::new(0, 18));
+        }
+        _ => panic!("unexpected error: {:?}", err),
+    }
+
+    let err = evaluator().evaluate("'abc'.startsWith(1)").unwrap_err();
+    assert!(matches!(
+        err,
+        EvaluatorError::InvalidArgumentType { index: 0, .. }
+    ));
+}
+
+#[cfg(feature = "string")]
+#[test]
+fn test_string_function_errors() {
+    // A string method that fails reports its own error rather than being
+    // mistaken for an unknown function.
+    let err = evaluator().evaluate("replace('abc', 'x')").unwrap_err();
+    match err {
+        EvaluatorError::InvalidArgumentCount {
+            function, found, ..
+        } => {
+            assert_eq!(function, "replace");
+            assert_eq!(found, 1);
+        }
+        _ => panic!("unexpected error: {:?}", err),
+    }
+    let err = evaluator().evaluate("startsWith('abc', 1)").unwrap_err();
+    assert!(matches!(
+        err,
+        EvaluatorError::InvalidArgumentType { index: 0, .. }
+    ));
+    let err = evaluator().evaluate("nope('abc')").unwrap_err();
+    assert!(matches!(err, EvaluatorError::UnknownFunction { .. }));
+}
+
+#[cfg(all(feature = "string", feature = "math"))]
+#[test]
+fn test_string_math_function_errors() {
+    assert_eq!(evaluator().evaluate("max('1', 2)").unwrap(), 2);
+    assert_eq!(evaluator().evaluate("floor('1.5')").unwrap(), 1);
+    let err = evaluator().evaluate("floor('abc', 1, 2)").unwrap_err();
+    assert!(matches!(err, EvaluatorError::UnknownFunction { .. }));
+    let err = evaluator().evaluate("nope('abc')").unwrap_err();
+    assert!(matches!(err, EvaluatorError::UnknownFunction { .. }));
+}
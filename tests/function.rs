@@ -0,0 +1,314 @@
+#[test]
+fn test_call_function_values() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert(
+        "items".to_string(),
+        ContextEntry::Variable(json!([{ "price": 2 }, { "price": 15 }])),
+    );
+    let evaluator = Evaluator::new(context);
+    assert_eq!(
+        evaluator
+            .evaluate_script("const discount = (p, pct) => p * (1 - pct); discount(100, 0.25)")
+            .unwrap(),
+        75
+    );
+    assert_eq!(
+        evaluator
+            .evaluate_script(
+                "const total = function (list) { let sum = 0; for (const x of list) sum += x.price; return sum; }; total(items)"
+            )
+            .unwrap(),
+        17
+    );
+    assert_eq!(evaluator.evaluate_script("(x => x * 3)(2)").unwrap(), 6);
+    assert_eq!(evaluator.evaluate("((a, b) => a - b)(5, 3)").unwrap(), 2);
+}
+
+#[test]
+fn test_function_arguments_and_result() {
+    use std::collections::HashMap;
+
+    use oxide_eval::Evaluator;
+    use serde_json::{json, Value};
+
+    let evaluator = Evaluator::new(HashMap::new());
+    // A block body without `return` gives `undefined`.
+    assert_eq!(
+        evaluator
+            .evaluate_script("const f = () => { 1; }; f()")
+            .unwrap(),
+        Value::Null
+    );
+    // Missing arguments are `undefined` and extra ones are ignored.
+    assert_eq!(
+        evaluator
+            .evaluate_script("const f = (a, b) => [a, b]; f(1)")
+            .unwrap(),
+        json!([1, null])
+    );
+    assert_eq!(
+        evaluator
+            .evaluate_script("const f = a => a; f(1, 2)")
+            .unwrap(),
+        1
+    );
+}
+
+#[test]
+fn test_functions_as_values() {
+    use std::collections::HashMap;
+
+    use oxide_eval::Evaluator;
+
+    let evaluator = Evaluator::new(HashMap::new());
+    assert_eq!(
+        evaluator
+            .evaluate_script("const fns = [x => x + 1, x => x * 10]; fns[1](4)")
+            .unwrap(),
+        40
+    );
+    assert_eq!(
+        evaluator
+            .evaluate_script("const add = a => b => a + b; add(1)(2)")
+            .unwrap(),
+        3
+    );
+    assert_eq!(
+        evaluator
+            .evaluate_script("const math = { square: x => x * x }; math.square(5)")
+            .unwrap(),
+        25
+    );
+}
+
+#[test]
+fn test_function_values_shadow_host_functions() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::{json, Value};
+
+    let mut context = HashMap::new();
+    context.insert(
+        "double".to_string(),
+        ContextEntry::Function(Box::new(|args| {
+            json!(args.first().and_then(Value::as_f64).unwrap_or_default() * 2.0)
+        })),
+    );
+    let evaluator = Evaluator::new(context);
+    assert_eq!(
+        evaluator
+            .evaluate_script("const double = x => x + x + 1; double(2)")
+            .unwrap(),
+        5
+    );
+    assert_eq!(evaluator.evaluate_script("double(2)").unwrap(), 4);
+}
+
+#[test]
+fn test_closures() {
+    use std::collections::HashMap;
+
+    use oxide_eval::Evaluator;
+    use serde_json::json;
+
+    let evaluator = Evaluator::new(HashMap::new());
+    assert_eq!(
+        evaluator
+            .evaluate_script("let count = 0; const next = () => ++count; next(); next(); count")
+            .unwrap(),
+        2
+    );
+    // Every call of `counter` has its own `n`.
+    assert_eq!(
+        evaluator
+            .evaluate_script(
+                "const counter = () => { let n = 0; return () => ++n; }; const a = counter(); const b = counter(); a(); a(); [a(), b()]"
+            )
+            .unwrap(),
+        json!([3, 1])
+    );
+    // Closures see later assignments to the variables they capture.
+    assert_eq!(
+        evaluator
+            .evaluate_script("let rate = 0.1; const tax = p => p * rate; rate = 0.5; tax(10)")
+            .unwrap(),
+        5
+    );
+    assert_eq!(
+        evaluator
+            .evaluate_script(
+                "const adders = []; for (const n of [1, 2]) adders[adders.length] = x => x + n; [adders[0](10), adders[1](10)]"
+            )
+            .unwrap(),
+        json!([11, 12])
+    );
+}
+
+#[test]
+fn test_recursion() {
+    use std::collections::HashMap;
+
+    use oxide_eval::Evaluator;
+
+    let evaluator = Evaluator::new(HashMap::new());
+    assert_eq!(
+        evaluator
+            .evaluate_script("const fact = n => n <= 1 ? 1 : n * fact(n - 1); fact(10)")
+            .unwrap(),
+        3628800
+    );
+    assert_eq!(
+        evaluator
+            .evaluate_script(
+                "const fib = function f(n) { if (n < 2) { return n; } return f(n - 1) + f(n - 2); }; fib(15)"
+            )
+            .unwrap(),
+        610
+    );
+    // The name of a function expression is only visible inside it.
+    assert_eq!(
+        evaluator
+            .evaluate_script("const f = function g(n) { return n; }; typeof g")
+            .unwrap(),
+        "undefined"
+    );
+    assert_eq!(
+        evaluator
+            .evaluate_script("(function g(g) { return g; })(7)")
+            .unwrap(),
+        7
+    );
+}
+
+#[test]
+fn test_call_depth_limit() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{Evaluator, EvaluatorError, DEFAULT_MAX_CALL_DEPTH};
+
+    let script = "const down = n => n === 0 ? 'done' : down(n - 1); down(depth)";
+    let evaluator = Evaluator::new(HashMap::new());
+    let res1 = evaluator
+        .evaluate_script(&script.replace("depth", &(DEFAULT_MAX_CALL_DEPTH - 1).to_string()))
+        .unwrap();
+    let err1 = evaluator
+        .evaluate_script(&script.replace("depth", &DEFAULT_MAX_CALL_DEPTH.to_string()))
+        .unwrap_err();
+    assert_eq!(res1, "done");
+    match err1 {
+        EvaluatorError::CallDepthExceeded { limit, span } => {
+            assert_eq!(limit, DEFAULT_MAX_CALL_DEPTH);
+            assert_eq!((span.start, span.end), (37, 48));
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+
+    let evaluator = evaluator.with_max_call_depth(3);
+    let res2 = evaluator
+        .evaluate_script(&script.replace("depth", "2"))
+        .unwrap();
+    let err2 = evaluator
+        .evaluate_script(&script.replace("depth", "3"))
+        .unwrap_err();
+    assert_eq!(res2, "done");
+    assert!(matches!(
+        err2,
+        EvaluatorError::CallDepthExceeded { limit: 3, .. }
+    ));
+}
+
+#[cfg(feature = "array")]
+#[test]
+fn test_callback_call_depth() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator, EvaluatorError};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert(
+        "items".to_string(),
+        ContextEntry::Variable(json!([{ "price": 2 }, { "price": 15 }])),
+    );
+    let evaluator = Evaluator::new(context).with_max_call_depth(3);
+    assert_eq!(
+        evaluator
+            .evaluate_script("const f = n => items.map(x => x.price * n); f(2)")
+            .unwrap(),
+        json!([4, 30])
+    );
+    // Callbacks count towards the limit too.
+    assert!(matches!(
+        evaluator
+            .evaluate_script("const f = n => [n].map(x => f(x + 1)); f(0)")
+            .unwrap_err(),
+        EvaluatorError::CallDepthExceeded { .. }
+    ));
+}
+
+#[test]
+fn test_function_errors() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{Evaluator, EvaluatorError};
+
+    let evaluator = Evaluator::new(HashMap::new());
+    match evaluator
+        .evaluate_script("const f = (a, b) => { let a = 1; }")
+        .unwrap_err()
+    {
+        EvaluatorError::Parse { errors, .. } => {
+            assert_eq!(
+                errors[0].message,
+                "Identifier 'a' has already been declared"
+            );
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+    match evaluator
+        .evaluate_script("const f = () => missing; f()")
+        .unwrap_err()
+    {
+        EvaluatorError::VariableNotFound { name, .. } => assert_eq!(name, "missing"),
+        e => panic!("unexpected error: {:?}", e),
+    }
+    assert!(matches!(
+        evaluator
+            .evaluate_script("const o = { n: 1 }; o.n()")
+            .unwrap_err(),
+        EvaluatorError::UnknownFunction { .. }
+    ));
+}
+
+#[test]
+fn test_unsupported_functions() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{Evaluator, EvaluatorError};
+
+    let evaluator = Evaluator::new(HashMap::new());
+    assert!(matches!(
+        evaluator
+            .evaluate_script("const f = async () => 1; f()")
+            .unwrap_err(),
+        EvaluatorError::UnsupportedSyntax { .. }
+    ));
+    assert!(matches!(
+        evaluator
+            .evaluate_script("const f = function* () {}; f()")
+            .unwrap_err(),
+        EvaluatorError::UnsupportedSyntax { .. }
+    ));
+    assert!(matches!(
+        evaluator
+            .evaluate_script("const f = ([a]) => a; f([1])")
+            .unwrap_err(),
+        EvaluatorError::UnsupportedSyntax { .. }
+    ));
+}
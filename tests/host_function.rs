@@ -0,0 +1,51 @@
+use oxide_eval::{context::ContextEntry, Evaluator, EvaluatorError, Span};
+use serde_json::{json, Value};
+use std::{collections::HashMap, error::Error};
+
+fn evaluator() -> Evaluator {
+    let mut context = HashMap::new();
+    context.insert(
+        "half".to_string(),
+        ContextEntry::FallibleFunction(Box::new(|args| {
+            let value = args
+                .first()
+                .and_then(Value::as_f64)
+                .ok_or("half expects a number")?;
+            Ok(json!(value / 2.0))
+        })),
+    );
+    context.insert(
+        "mul".to_string(),
+        ContextEntry::Function(Box::new(|args| {
+            json!(args[0].as_f64().unwrap() * args[1].as_f64().unwrap())
+        })),
+    );
+    Evaluator::new(context)
+}
+
+#[test]
+fn test_fallible_function() {
+    let evaluator = evaluator();
+    assert_eq!(evaluator.evaluate("half(5)").unwrap(), 2.5);
+    assert_eq!(evaluator.evaluate("mul(half(8), 3)").unwrap(), 12.0);
+}
+
+#[test]
+fn test_fallible_function_error() {
+    let err = evaluator().evaluate("1 + half('x')").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Function \"half\" failed: half expects a number"
+    );
+    assert_eq!(
+        err.source().map(|e| e.to_string()),
+        Some("half expects a number".to_string())
+    );
+    match err {
+        EvaluatorError::HostFunction { name, span, .. } => {
+            assert_eq!(name, "half");
+            assert_eq!(span, Span::new(4, 13));
+        }
+        _ => panic!("unexpected error: {:?}", err),
+    }
+}
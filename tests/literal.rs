@@ -0,0 +1,228 @@
+#[test]
+fn test_object_literal_keys() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert("field".to_string(), ContextEntry::Variable(json!("size")));
+    context.insert("size".to_string(), ContextEntry::Variable(json!(14)));
+    let evaluator = Evaluator::new(context);
+    assert_eq!(evaluator.evaluate("({ a: 1 })").unwrap(), json!({ "a": 1 }));
+    assert_eq!(
+        evaluator.evaluate("({ \"a b\": 1, 'c': 2 })").unwrap(),
+        json!({ "a b": 1, "c": 2 })
+    );
+    // Numeric keys are converted to strings like numbers are.
+    assert_eq!(
+        evaluator
+            .evaluate("({ 1: 'x', 1.50: 'y', 0x10: 'z' })")
+            .unwrap(),
+        json!({ "1": "x", "1.5": "y", "16": "z" })
+    );
+    assert_eq!(
+        evaluator.evaluate("({ size })").unwrap(),
+        json!({ "size": 14 })
+    );
+    assert_eq!(
+        evaluator
+            .evaluate("({ [field]: 1, [field + 2]: 2 })")
+            .unwrap(),
+        json!({ "size": 1, "size2": 2 })
+    );
+    assert_eq!(
+        evaluator.evaluate("({ [1 + 1]: true })").unwrap(),
+        json!({ "2": true })
+    );
+    // The last of duplicate keys wins.
+    assert_eq!(
+        evaluator.evaluate("({ a: 1, a: 2 })").unwrap(),
+        json!({ "a": 2 })
+    );
+    assert_eq!(
+        evaluator
+            .evaluate("({ undefined: 1, null: 2 }).null")
+            .unwrap(),
+        2
+    );
+}
+
+#[test]
+fn test_object_literal_values() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::{json, Value};
+
+    let mut context = HashMap::new();
+    context.insert("size".to_string(), ContextEntry::Variable(json!(14)));
+    let evaluator = Evaluator::new(context);
+    assert_eq!(evaluator.evaluate("({})").unwrap(), json!({}));
+    assert_eq!(
+        evaluator
+            .evaluate("({ a: { b: [1, { c: 2 }] } }).a.b[1].c")
+            .unwrap(),
+        2
+    );
+    assert_eq!(evaluator.evaluate("({ size: 1 }).size + size").unwrap(), 15);
+    assert_eq!(
+        evaluator.evaluate("({ a: undefined })").unwrap(),
+        json!({ "a": Value::Null })
+    );
+}
+
+#[test]
+fn test_object_spread() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert(
+        "defaults".to_string(),
+        ContextEntry::Variable(json!({ "theme": "light", "size": 12 })),
+    );
+    context.insert(
+        "items".to_string(),
+        ContextEntry::Variable(json!([1, 2, 3])),
+    );
+    context.insert("size".to_string(), ContextEntry::Variable(json!(14)));
+    let evaluator = Evaluator::new(context);
+    // Later properties override earlier ones, whichever way they were added.
+    assert_eq!(
+        evaluator.evaluate("({ ...defaults, size })").unwrap(),
+        json!({ "theme": "light", "size": 14 })
+    );
+    assert_eq!(
+        evaluator.evaluate("({ size, ...defaults })").unwrap(),
+        json!({ "theme": "light", "size": 12 })
+    );
+    assert_eq!(
+        evaluator.evaluate("({ ...items })").unwrap(),
+        json!({ "0": 1, "1": 2, "2": 3 })
+    );
+    assert_eq!(
+        evaluator.evaluate("({ ...'hi' })").unwrap(),
+        json!({ "0": "h", "1": "i" })
+    );
+    // Spreading a value without properties adds nothing.
+    assert_eq!(
+        evaluator
+            .evaluate("({ ...null, ...undefined, ...1, ...true })")
+            .unwrap(),
+        json!({})
+    );
+    // A spread copies the properties into a new object.
+    assert_eq!(
+        evaluator
+            .evaluate("({ ...defaults }) !== defaults")
+            .unwrap(),
+        true
+    );
+}
+
+#[test]
+fn test_array_literal() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert(
+        "items".to_string(),
+        ContextEntry::Variable(json!([1, 2, 3])),
+    );
+    let evaluator = Evaluator::new(context);
+    assert_eq!(evaluator.evaluate("[]").unwrap(), json!([]));
+    assert_eq!(
+        evaluator.evaluate("[...items, 4]").unwrap(),
+        json!([1, 2, 3, 4])
+    );
+    assert_eq!(
+        evaluator
+            .evaluate("[0, ...items, ...[], ...[4, 5]]")
+            .unwrap(),
+        json!([0, 1, 2, 3, 4, 5])
+    );
+    // Strings spread into code points, not UTF-16 code units.
+    assert_eq!(
+        evaluator.evaluate("[...'a😀']").unwrap(),
+        json!(["a", "😀"])
+    );
+    // Spreading copies the array but not the elements.
+    assert_eq!(
+        evaluator
+            .evaluate("[...[[1]]][0] === [...[[1]]][0]")
+            .unwrap(),
+        false
+    );
+}
+
+#[test]
+fn test_array_literal_holes() {
+    use std::collections::HashMap;
+
+    use oxide_eval::Evaluator;
+    use serde_json::json;
+
+    let evaluator = Evaluator::new(HashMap::new());
+    assert_eq!(evaluator.evaluate("[1, , 3]").unwrap(), json!([1, null, 3]));
+    assert_eq!(
+        evaluator.evaluate("[1, , 3][1] === undefined").unwrap(),
+        true
+    );
+    assert_eq!(evaluator.evaluate("[, ,].length").unwrap(), 2);
+    // A trailing comma doesn't add a hole.
+    assert_eq!(evaluator.evaluate("[1, 2,].length").unwrap(), 2);
+}
+
+#[test]
+fn test_literal_errors() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator, EvaluatorError};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert(
+        "defaults".to_string(),
+        ContextEntry::Variable(json!({ "theme": "light" })),
+    );
+    let evaluator = Evaluator::new(context);
+    match evaluator.evaluate("[1, ...defaults]").unwrap_err() {
+        EvaluatorError::TypeError { message, span } => {
+            assert_eq!(message, "[object Object] is not iterable");
+            assert_eq!((span.start, span.end), (7, 15));
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+    assert!(matches!(
+        evaluator.evaluate("[...null]").unwrap_err(),
+        EvaluatorError::TypeError { .. }
+    ));
+    assert!(matches!(
+        evaluator.evaluate("[...1]").unwrap_err(),
+        EvaluatorError::TypeError { .. }
+    ));
+    assert!(matches!(
+        evaluator
+            .evaluate("({ get a() { return 1 } })")
+            .unwrap_err(),
+        EvaluatorError::UnsupportedSyntax { .. }
+    ));
+    match evaluator.evaluate("({ [missing]: 1 })").unwrap_err() {
+        EvaluatorError::VariableNotFound { name, .. } => assert_eq!(name, "missing"),
+        e => panic!("unexpected error: {:?}", e),
+    }
+    assert!(matches!(
+        evaluator.evaluate("({ ...missing })").unwrap_err(),
+        EvaluatorError::VariableNotFound { .. }
+    ));
+    assert!(matches!(
+        evaluator.evaluate("[...missing]").unwrap_err(),
+        EvaluatorError::VariableNotFound { .. }
+    ));
+}
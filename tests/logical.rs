@@ -0,0 +1,78 @@
+use oxide_eval::{context::ContextEntry, Evaluator};
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+fn evaluator(calls: Arc<AtomicUsize>) -> Evaluator {
+    let mut context = HashMap::new();
+    context.insert("user".to_string(), ContextEntry::Variable(Value::Null));
+    context.insert(
+        "admin".to_string(),
+        ContextEntry::Variable(json!({ "name": "root" })),
+    );
+    context.insert(
+        "track".to_string(),
+        ContextEntry::Function(Box::new(move |args| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            args.into_iter().next().unwrap_or(Value::Null)
+        })),
+    );
+    Evaluator::new(context)
+}
+
+#[test]
+fn test_short_circuit_skips_errors() {
+    let evaluator = evaluator(Arc::new(AtomicUsize::new(0)));
+    assert_eq!(
+        evaluator.evaluate("user && user.name").unwrap(),
+        Value::Null
+    );
+    assert_eq!(evaluator.evaluate("admin && admin.name").unwrap(), "root");
+    assert_eq!(
+        evaluator.evaluate("admin || missing").unwrap(),
+        json!({ "name": "root" })
+    );
+    assert_eq!(
+        evaluator.evaluate("admin ?? missing").unwrap(),
+        json!({ "name": "root" })
+    );
+    assert_eq!(evaluator.evaluate("true ? 1 : missing").unwrap(), 1);
+    assert_eq!(evaluator.evaluate("false ? missing : 2").unwrap(), 2);
+    assert!(evaluator.evaluate("admin && missing").is_err());
+    assert!(evaluator.evaluate("user || missing").is_err());
+    assert!(evaluator.evaluate("user ?? missing").is_err());
+}
+
+#[test]
+fn test_short_circuit_skips_side_effects() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let evaluator = evaluator(calls.clone());
+
+    evaluator.evaluate("false && track(1)").unwrap();
+    evaluator.evaluate("1 || track(1)").unwrap();
+    evaluator.evaluate("0 ?? track(1)").unwrap();
+    evaluator.evaluate("true ? 1 : track(1)").unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+    evaluator.evaluate("true && track(1)").unwrap();
+    evaluator.evaluate("'' || track(1)").unwrap();
+    evaluator.evaluate("null ?? track(1)").unwrap();
+    evaluator.evaluate("false ? 1 : track(1)").unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+fn test_operand_results() {
+    let evaluator = evaluator(Arc::new(AtomicUsize::new(0)));
+    assert_eq!(evaluator.evaluate("1 && 'a'").unwrap(), "a");
+    assert_eq!(evaluator.evaluate("0 && 'a'").unwrap(), 0);
+    assert_eq!(evaluator.evaluate("'' && 'a'").unwrap(), "");
+    assert_eq!(evaluator.evaluate("0 || ''").unwrap(), "");
+    assert_eq!(evaluator.evaluate("0 ?? 5").unwrap(), 0);
+    assert_eq!(evaluator.evaluate("null ?? 5").unwrap(), 5);
+}
@@ -0,0 +1,301 @@
+#[test]
+fn test_computed_member() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert(
+        "items".to_string(),
+        ContextEntry::Variable(json!([
+            { "name": "apple", "price": 3 },
+            { "name": "pear", "price": 5 },
+        ])),
+    );
+    context.insert(
+        "attrs".to_string(),
+        ContextEntry::Variable(json!({ "color": "red", "1": "one" })),
+    );
+    context.insert(
+        "fieldName".to_string(),
+        ContextEntry::Variable(json!("color")),
+    );
+    context.insert("i".to_string(), ContextEntry::Variable(json!(0)));
+    let evaluator = Evaluator::new(context);
+    assert_eq!(evaluator.evaluate("items[0].price").unwrap(), 3);
+    assert_eq!(evaluator.evaluate("items[i + 1].name").unwrap(), "pear");
+    assert_eq!(evaluator.evaluate("items['1'].name").unwrap(), "pear");
+    assert_eq!(evaluator.evaluate("attrs[fieldName]").unwrap(), "red");
+    assert_eq!(evaluator.evaluate("attrs['col' + 'or']").unwrap(), "red");
+    assert_eq!(evaluator.evaluate("attrs[1]").unwrap(), "one");
+    assert_eq!(evaluator.evaluate("[[1, 2], [3, 4]][1][0]").unwrap(), 3);
+    assert_eq!(evaluator.evaluate("'abc'[1]").unwrap(), "b");
+}
+
+#[test]
+fn test_negative_index() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert(
+        "items".to_string(),
+        ContextEntry::Variable(json!([{ "name": "apple" }, { "name": "pear" }])),
+    );
+    let evaluator = Evaluator::new(context);
+    assert_eq!(evaluator.evaluate("items[-1].name").unwrap(), "pear");
+    assert_eq!(evaluator.evaluate("items[-2].name").unwrap(), "apple");
+    assert_eq!(evaluator.evaluate("'abc'[-1]").unwrap(), "c");
+    assert_eq!(evaluator.evaluate("[1, 2, 3][-3]").unwrap(), 1);
+}
+
+#[test]
+fn test_optional_computed_member() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::{json, Value};
+
+    let mut context = HashMap::new();
+    context.insert(
+        "items".to_string(),
+        ContextEntry::Variable(json!([{ "price": 3 }, { "price": 5 }])),
+    );
+    context.insert(
+        "attrs".to_string(),
+        ContextEntry::Variable(json!({ "color": "red" })),
+    );
+    context.insert("missing".to_string(), ContextEntry::Variable(Value::Null));
+    let evaluator = Evaluator::new(context);
+    assert_eq!(evaluator.evaluate("missing?.[0]").unwrap(), Value::Null);
+    assert_eq!(evaluator.evaluate("undeclared?.[0]").unwrap(), Value::Null);
+    assert_eq!(evaluator.evaluate("items?.[5]").unwrap(), Value::Null);
+    assert_eq!(evaluator.evaluate("items?.[-5]").unwrap(), Value::Null);
+    assert_eq!(evaluator.evaluate("attrs?.['size'] ?? 'M'").unwrap(), "M");
+    assert_eq!(evaluator.evaluate("items?.[1].price").unwrap(), 5);
+}
+
+#[test]
+fn test_computed_member_errors() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator, EvaluatorError};
+    use serde_json::{json, Value};
+
+    let mut context = HashMap::new();
+    context.insert("items".to_string(), ContextEntry::Variable(json!([1, 2])));
+    context.insert(
+        "attrs".to_string(),
+        ContextEntry::Variable(json!({ "color": "red" })),
+    );
+    context.insert(
+        "fieldName".to_string(),
+        ContextEntry::Variable(json!("color")),
+    );
+    context.insert("missing".to_string(), ContextEntry::Variable(Value::Null));
+    let evaluator = Evaluator::new(context);
+    match evaluator.evaluate("items[2]").unwrap_err() {
+        EvaluatorError::IndexOutOfRange {
+            index,
+            length,
+            span,
+        } => {
+            assert_eq!((index, length), (2, 2));
+            assert_eq!((span.start, span.end), (0, 8));
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+    assert!(matches!(
+        evaluator.evaluate("items[-3]").unwrap_err(),
+        EvaluatorError::IndexOutOfRange { index: -3, .. }
+    ));
+    assert!(matches!(
+        evaluator.evaluate("'abc'[3]").unwrap_err(),
+        EvaluatorError::IndexOutOfRange {
+            index: 3,
+            length: 3,
+            ..
+        }
+    ));
+    match evaluator.evaluate("attrs[fieldName + 's']").unwrap_err() {
+        EvaluatorError::PropertyNotFound { property, .. } => assert_eq!(property, "colors"),
+        e => panic!("unexpected error: {:?}", e),
+    }
+    assert_eq!(
+        evaluator.evaluate("missing[0]").unwrap_err().to_string(),
+        "Cannot read properties of null (reading '0')"
+    );
+    match evaluator.evaluate("items[nothere]").unwrap_err() {
+        EvaluatorError::VariableNotFound { name, .. } => assert_eq!(name, "nothere"),
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[test]
+fn test_static_member_on_arrays_and_primitives() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::{json, Value};
+
+    let mut context = HashMap::new();
+    context.insert(
+        "items".to_string(),
+        ContextEntry::Variable(json!([{ "name": "apple" }, { "name": "pear" }])),
+    );
+    let evaluator = Evaluator::new(context);
+    assert_eq!(evaluator.evaluate("items.length").unwrap(), 2);
+    assert_eq!(evaluator.evaluate("[1, 2, 3].length").unwrap(), 3);
+    assert_eq!(evaluator.evaluate("[].length").unwrap(), 0);
+    assert_eq!(evaluator.evaluate("'abc'.length").unwrap(), 3);
+    assert_eq!(evaluator.evaluate("''.length").unwrap(), 0);
+    assert_eq!(evaluator.evaluate("'😀'.length").unwrap(), 2);
+    assert_eq!(evaluator.evaluate("items[0].name.length").unwrap(), 5);
+    assert_eq!(evaluator.evaluate("items['length']").unwrap(), 2);
+    assert_eq!(evaluator.evaluate("'abc'.foo").unwrap(), Value::Null);
+    assert_eq!(evaluator.evaluate("(1).foo ?? 'none'").unwrap(), "none");
+    assert_eq!(evaluator.evaluate("true.foo === undefined").unwrap(), true);
+}
+
+#[test]
+fn test_strict_mode_rejects_primitive_properties() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{Evaluator, EvaluatorError};
+    use serde_json::Value;
+
+    let evaluator = Evaluator::new(HashMap::new()).with_strict(true);
+    assert_eq!(evaluator.evaluate("'abc'.length").unwrap(), 3);
+    match evaluator.evaluate("'abc'.foo").unwrap_err() {
+        EvaluatorError::PropertyNotFound { property, .. } => assert_eq!(property, "foo"),
+        e => panic!("unexpected error: {:?}", e),
+    }
+    assert!(matches!(
+        evaluator.evaluate("(1).foo").unwrap_err(),
+        EvaluatorError::PropertyNotFound { .. }
+    ));
+    assert_eq!(evaluator.evaluate("'abc'?.foo").unwrap(), Value::Null);
+}
+
+#[test]
+fn test_nullish_member_access() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator, EvaluatorError};
+    use serde_json::{json, Value};
+
+    let mut context = HashMap::new();
+    context.insert(
+        "attrs".to_string(),
+        ContextEntry::Variable(json!({ "color": "red" })),
+    );
+    context.insert("missing".to_string(), ContextEntry::Variable(Value::Null));
+    let evaluator = Evaluator::new(context);
+    match evaluator.evaluate("missing.name").unwrap_err() {
+        EvaluatorError::TypeError { message, span } => {
+            assert_eq!(message, "Cannot read properties of null (reading 'name')");
+            assert_eq!((span.start, span.end), (0, 12));
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+    assert_eq!(
+        evaluator
+            .evaluate("undefined.name")
+            .unwrap_err()
+            .to_string(),
+        "Cannot read properties of undefined (reading 'name')"
+    );
+    assert!(matches!(
+        evaluator.evaluate("attrs?.size.name").unwrap_err(),
+        EvaluatorError::TypeError { .. }
+    ));
+    assert_eq!(evaluator.evaluate("missing?.name").unwrap(), Value::Null);
+    // `?.` short-circuits the rest of the chain.
+    assert_eq!(
+        evaluator.evaluate("missing?.name.first").unwrap(),
+        Value::Null
+    );
+    assert_eq!(
+        evaluator.evaluate("missing?.[0].name[1]").unwrap(),
+        Value::Null
+    );
+}
+
+#[test]
+fn test_optional_chain_ends_at_parentheses() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator, EvaluatorError};
+    use serde_json::{json, Value};
+
+    let mut context = HashMap::new();
+    context.insert(
+        "attrs".to_string(),
+        ContextEntry::Variable(json!({ "color": "red" })),
+    );
+    context.insert("missing".to_string(), ContextEntry::Variable(Value::Null));
+    let evaluator = Evaluator::new(context);
+    match evaluator.evaluate("(missing?.name).first").unwrap_err() {
+        EvaluatorError::TypeError { message, span } => {
+            assert_eq!(
+                message,
+                "Cannot read properties of undefined (reading 'first')"
+            );
+            assert_eq!((span.start, span.end), (0, 21));
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+    assert!(matches!(
+        evaluator.evaluate("(missing?.[0])[1]").unwrap_err(),
+        EvaluatorError::TypeError { .. }
+    ));
+    assert_eq!(evaluator.evaluate("(missing?.name)").unwrap(), Value::Null);
+    assert_eq!(
+        evaluator.evaluate("(missing?.name)?.first").unwrap(),
+        Value::Null
+    );
+    assert_eq!(evaluator.evaluate("(attrs?.color).length").unwrap(), 3);
+    // Calls inside the chain are skipped along with the rest of it.
+    assert_eq!(
+        evaluator.evaluate("missing?.name.trim().length").unwrap(),
+        Value::Null
+    );
+    assert_eq!(evaluator.evaluate("missing?.[0]()").unwrap(), Value::Null);
+}
+
+#[test]
+fn test_optional_chain_reports_missing_variables() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator, EvaluatorError};
+    use serde_json::{json, Value};
+
+    let mut context = HashMap::new();
+    context.insert("list".to_string(), ContextEntry::Variable(json!([1, 2])));
+    context.insert(
+        "first".to_string(),
+        ContextEntry::Function(Box::new(|args| args[0].clone())),
+    );
+    let evaluator = Evaluator::new(context);
+    assert_eq!(evaluator.evaluate("undeclared?.x").unwrap(), Value::Null);
+    match evaluator.evaluate("first(nothere)?.x").unwrap_err() {
+        EvaluatorError::VariableNotFound { name, .. } => assert_eq!(name, "nothere"),
+        e => panic!("unexpected error: {:?}", e),
+    }
+    match evaluator.evaluate("list[nothere]?.x").unwrap_err() {
+        EvaluatorError::VariableNotFound { name, .. } => assert_eq!(name, "nothere"),
+        e => panic!("unexpected error: {:?}", e),
+    }
+    match evaluator.evaluate("(1 + nothere)?.x").unwrap_err() {
+        EvaluatorError::VariableNotFound { name, .. } => assert_eq!(name, "nothere"),
+        e => panic!("unexpected error: {:?}", e),
+    }
+    // The key isn't evaluated once the chain has short-circuited.
+    assert_eq!(
+        evaluator.evaluate("undeclared?.[nothere]").unwrap(),
+        Value::Null
+    );
+}
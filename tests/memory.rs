@@ -0,0 +1,62 @@
+use oxide_eval::Evaluator;
+use serde_json::json;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    collections::HashMap,
+    sync::atomic::{AtomicIsize, Ordering},
+};
+
+/// Counts the bytes currently allocated, to find values that are never freed.
+struct Counting;
+
+static ALLOCATED: AtomicIsize = AtomicIsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size() as isize, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size() as isize, Ordering::SeqCst);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+// The only test in this file, so nothing else allocates while it runs.
+#[test]
+fn test_reference_cycles_are_freed() {
+    let evaluator = Evaluator::new(HashMap::new());
+    let scripts = [
+        "const f = n => n > 0 ? f(n - 1) : 0; f(3)",
+        "const o = {}; o.self = o; 1",
+        "const list = [1]; list[1] = list; list.length",
+        "const a = {}; const b = { a }; a.b = b; 1",
+        "const o = {}; o.f = () => o; o.f() === o",
+        "let counter = () => { let n = 0; const next = () => ++n; return next; }; counter()()",
+        "const list = [[]]; list[0][0] = list; list[0].length",
+    ];
+    for script in scripts {
+        // Warm up anything allocated once, such as lazily initialized statics.
+        evaluator.evaluate_script(script).unwrap();
+        let before = ALLOCATED.load(Ordering::SeqCst);
+        for _ in 0..10 {
+            evaluator.evaluate_script(script).unwrap();
+        }
+        let leaked = ALLOCATED.load(Ordering::SeqCst) - before;
+        assert_eq!(leaked, 0, "{}", script);
+    }
+    // Results with a cycle can't be returned, but are freed as well.
+    let variables = HashMap::from([("order".to_string(), json!({ "count": 1 }))]);
+    let run = || {
+        let result = evaluator.evaluate_mut("order.self = order", variables.clone());
+        assert!(result.is_err());
+    };
+    run();
+    let before = ALLOCATED.load(Ordering::SeqCst);
+    run();
+    assert_eq!(ALLOCATED.load(Ordering::SeqCst) - before, 0);
+}
@@ -0,0 +1,72 @@
+use oxide_eval::{context::ContextEntry, Evaluator};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+fn base() -> Evaluator {
+    let mut context = HashMap::new();
+    context.insert("rate".to_string(), ContextEntry::Variable(json!(2)));
+    context.insert("region".to_string(), ContextEntry::Variable(json!("eu")));
+    context.insert(
+        "scale".to_string(),
+        ContextEntry::Function(Box::new(|args| json!(args[0].as_f64().unwrap() * 10.0))),
+    );
+    Evaluator::new(context)
+}
+
+#[test]
+fn test_overlay_variables() {
+    let evaluator = base();
+
+    let mut request = HashMap::new();
+    request.insert("amount".to_string(), json!(21));
+    assert_eq!(
+        evaluator.evaluate_with("amount * rate", &request).unwrap(),
+        42
+    );
+
+    // Overlay entries shadow the base context.
+    request.insert("rate".to_string(), json!(3));
+    assert_eq!(
+        evaluator.evaluate_with("amount * rate", &request).unwrap(),
+        63
+    );
+    assert_eq!(evaluator.evaluate("rate").unwrap(), 2);
+    assert!(evaluator.evaluate("amount").is_err());
+}
+
+#[test]
+fn test_overlay_functions() {
+    let evaluator = base();
+
+    let mut request = HashMap::new();
+    request.insert("region".to_string(), ContextEntry::Variable(json!("us")));
+    request.insert(
+        "scale".to_string(),
+        ContextEntry::Function(Box::new(|args| json!(args[0].as_f64().unwrap() * 100.0))),
+    );
+    assert_eq!(
+        evaluator
+            .evaluate_with("region == 'us' ? scale(rate) : 0", &request)
+            .unwrap(),
+        200.0
+    );
+    assert_eq!(evaluator.evaluate("scale(rate)").unwrap(), 20.0);
+}
+
+#[test]
+fn test_overlay_compiled() {
+    let evaluator = base();
+    let compiled = Evaluator::compile("scale(x) + rate").unwrap();
+
+    for x in 0..5 {
+        let overlay = HashMap::from([("x".to_string(), json!(x))]);
+        assert_eq!(
+            evaluator
+                .evaluate_compiled_with(&compiled, &overlay)
+                .unwrap(),
+            x * 10 + 2
+        );
+    }
+    let empty: HashMap<String, Value> = HashMap::new();
+    assert!(evaluator.evaluate_compiled_with(&compiled, &empty).is_err());
+}
@@ -0,0 +1,88 @@
+use oxide_eval::{
+    context::{ContextEntry, ContextProvider},
+    Evaluator,
+};
+use serde_json::{json, Value};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+/// Computes variables on request, e.g. from a database.
+struct LazyProvider {
+    fetches: Arc<AtomicUsize>,
+}
+
+impl ContextProvider for LazyProvider {
+    fn variable(&self, name: &str) -> Option<Cow<'_, Value>> {
+        self.fetches.fetch_add(1, Ordering::SeqCst);
+        let id = name.strip_prefix("user_")?.parse::<u64>().ok()?;
+        Some(Cow::Owned(json!({ "id": id, "score": id * 10 })))
+    }
+}
+
+/// Falls back to a lowercase lookup when the exact name is missing.
+struct CaseInsensitive(HashMap<String, ContextEntry>);
+
+impl ContextProvider for CaseInsensitive {
+    fn variable(&self, name: &str) -> Option<Cow<'_, Value>> {
+        self.0
+            .variable(name)
+            .or_else(|| self.0.variable(&name.to_lowercase()))
+    }
+
+    fn function(&self, name: &str) -> Option<&ContextEntry> {
+        self.0
+            .function(name)
+            .or_else(|| self.0.function(&name.to_lowercase()))
+    }
+}
+
+#[test]
+fn test_lazy_provider() {
+    let fetches = Arc::new(AtomicUsize::new(0));
+    let evaluator = Evaluator::with_provider(LazyProvider {
+        fetches: fetches.clone(),
+    });
+
+    assert_eq!(evaluator.evaluate("user_4.score + user_2.id").unwrap(), 42);
+    assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    assert!(evaluator.evaluate("admin").is_err());
+}
+
+#[test]
+fn test_case_insensitive_provider() {
+    let mut context = HashMap::new();
+    context.insert("limit".to_string(), ContextEntry::Variable(json!(5)));
+    context.insert(
+        "inc".to_string(),
+        ContextEntry::Function(Box::new(|args| json!(args[0].as_i64().unwrap() + 1))),
+    );
+    let evaluator = Evaluator::with_provider(CaseInsensitive(context));
+
+    assert_eq!(evaluator.evaluate("INC(Limit)").unwrap(), 6);
+}
+
+#[test]
+fn test_chained_providers() {
+    let mut overrides = HashMap::new();
+    overrides.insert("a".to_string(), ContextEntry::Variable(json!(1)));
+    let mut defaults = HashMap::new();
+    defaults.insert("a".to_string(), ContextEntry::Variable(json!(100)));
+    defaults.insert("b".to_string(), ContextEntry::Variable(json!(2)));
+
+    let chain: Vec<Box<dyn ContextProvider>> = vec![
+        Box::new(overrides),
+        Box::new(defaults),
+        Box::new(LazyProvider {
+            fetches: Arc::new(AtomicUsize::new(0)),
+        }),
+    ];
+    let evaluator = Evaluator::with_provider(chain);
+
+    assert_eq!(evaluator.evaluate("a + b + user_3.score").unwrap(), 33);
+}
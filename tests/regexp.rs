@@ -0,0 +1,194 @@
+#![cfg(feature = "string")]
+
+use oxide_eval::{context::ContextEntry, Evaluator, EvaluatorError};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+fn evaluator() -> Evaluator {
+    let mut context = HashMap::new();
+    context.insert(
+        "date".to_string(),
+        ContextEntry::Variable(json!("2024-03-15")),
+    );
+    context.insert(
+        "csv".to_string(),
+        ContextEntry::Variable(json!("a, b,c ,  d")),
+    );
+    Evaluator::new(context)
+}
+
+#[test]
+fn test_regexp_methods() {
+    let evaluator = evaluator();
+    let cases = [
+        (r"/^\d{4}-\d{2}-\d{2}$/.test(date)", json!(true)),
+        (r"/^\d+$/.test('12a')", json!(false)),
+        ("/abc/i.test('xABCx')", json!(true)),
+        ("/^b/m.test('a\\nb')", json!(true)),
+        ("/^b/.test('a\\nb')", json!(false)),
+        ("/a.b/.test('a\\nb')", json!(false)),
+        ("/a.b/s.test('a\\nb')", json!(true)),
+        (r"/\d/.test('٣')", json!(false)),
+        (r"/a\B/.test('ab') && /a\b/.test('a b')", json!(true)),
+        (r"/😀/u.test('a😀')", json!(true)),
+        (r"/\u{1F600}/u.test('😀')", json!(true)),
+        (r"/[\]]/.test(']')", json!(true)),
+        (
+            "/(\\d+)-(\\d+)/.exec('10-20')",
+            json!(["10-20", "10", "20"]),
+        ),
+        ("/x/.exec('abc')", Value::Null),
+        ("/b/.exec('abc').index", json!(1)),
+        ("/b/.exec('😀b').index", json!(2)),
+        ("/b/.exec('abc').input", json!("abc")),
+        ("/a(x)?/.exec('a')", json!(["a", null])),
+        ("/ab+c/gi.source", json!("ab+c")),
+        ("/ab+c/gi.flags", json!("gi")),
+        ("/ab+c/gi.global", json!(true)),
+        ("/ab+c/gi.multiline", json!(false)),
+        ("'' + /a/g", json!("/a/g")),
+        ("/a/", json!({})),
+    ];
+    for (expression, expected) in cases {
+        assert_eq!(
+            evaluator.evaluate(expression).unwrap(),
+            expected,
+            "{}",
+            expression
+        );
+    }
+}
+
+#[test]
+fn test_capture_groups() {
+    let evaluator = evaluator();
+    let cases = [
+        (
+            r"date.match(/(\d+)-(\d+)/)",
+            json!(["2024-03", "2024", "03"]),
+        ),
+        (r"date.match(/(\d+)-(\d+)/)[2]", json!("03")),
+        (
+            r"date.match(/(?<year>\d{4})-(?<month>\d{2})/).groups",
+            json!({ "year": "2024", "month": "03" }),
+        ),
+        (
+            r"date.match(/(?<year>\d{4})-(?<month>\d{2})/).groups.month",
+            json!("03"),
+        ),
+        (r"date.match(/\d+/).groups", Value::Null),
+        (r"date.match(/\d+/g)", json!(["2024", "03", "15"])),
+        (r"date.match(/x/g)", Value::Null),
+        ("'a.b'.match('.')", json!(["a"])),
+        (r"date.search(/-/)", json!(4)),
+        (r"date.search(/x/)", json!(-1)),
+    ];
+    for (expression, expected) in cases {
+        assert_eq!(
+            evaluator.evaluate(expression).unwrap(),
+            expected,
+            "{}",
+            expression
+        );
+    }
+}
+
+#[cfg(feature = "array")]
+#[test]
+fn test_match_all() {
+    let evaluator = evaluator();
+    assert_eq!(
+        evaluator
+            .evaluate(r"'a1b22'.matchAll(/[a-z](\d+)/g).map(m => m[1])")
+            .unwrap(),
+        json!(["1", "22"])
+    );
+    assert_eq!(
+        evaluator
+            .evaluate(r"'a1b22'.matchAll(/[a-z](?<n>\d+)/g).map(m => m.groups.n + '@' + m.index)")
+            .unwrap(),
+        json!(["1@0", "22@2"])
+    );
+}
+
+#[test]
+fn test_replace_and_split() {
+    let evaluator = evaluator();
+    let cases = [
+        (
+            r"date.replace(/(\d+)-(\d+)-(\d+)/, '$3/$2/$1')",
+            json!("15/03/2024"),
+        ),
+        (
+            r"date.replace(/(?<y>\d+)-(?<m>\d+)-(?<d>\d+)/, '$<d>.$<m>.$<y>')",
+            json!("15.03.2024"),
+        ),
+        (r"date.replace(/\d+/, 'N')", json!("N-03-15")),
+        (r"date.replace(/\d+/g, 'N')", json!("N-N-N")),
+        (r"date.replaceAll(/-/g, '')", json!("20240315")),
+        (
+            "'abc'.replace(/b/, '[$&|$`|$\\'|$$]')",
+            json!("a[b|a|c|$]c"),
+        ),
+        (r"'abc'.replace(/(b)/, '$2$1$0')", json!("a$2b$0c")),
+        ("'aaa'.replace(/a/gi, 'b')", json!("bbb")),
+        (r"csv.split(/\s*,\s*/)", json!(["a", "b", "c", "d"])),
+        (r"csv.split(/\s*(,)\s*/, 3)", json!(["a", ",", "b"])),
+        (r"'abc'.split(/(?:)/)", json!(["a", "b", "c"])),
+        (r"'a1b'.split(/(\d)|x/)", json!(["a", "1", "b"])),
+        (r"''.split(/x/)", json!([""])),
+        (r"''.split(/(?:)/)", json!([])),
+        (r"'ab'.split(/x*/)", json!(["a", "b"])),
+    ];
+    for (expression, expected) in cases {
+        assert_eq!(
+            evaluator.evaluate(expression).unwrap(),
+            expected,
+            "{}",
+            expression
+        );
+    }
+}
+
+#[test]
+fn test_regexp_identity() {
+    let evaluator = evaluator();
+    assert_eq!(evaluator.evaluate("/a/ == /a/").unwrap(), false);
+    #[cfg(feature = "array")]
+    assert_eq!(
+        evaluator.evaluate("[/a/].map(r => r === r)").unwrap(),
+        json!([true])
+    );
+}
+
+#[test]
+fn test_regexp_errors() {
+    let evaluator = evaluator();
+    assert!(matches!(
+        evaluator.evaluate("'aa'.replaceAll(/a/, 'b')").unwrap_err(),
+        EvaluatorError::TypeError { .. }
+    ));
+    assert!(matches!(
+        evaluator.evaluate("'aa'.matchAll(/a/)").unwrap_err(),
+        EvaluatorError::TypeError { .. }
+    ));
+    match evaluator.evaluate(r"1 + /(a)\1/.test('aa')").unwrap_err() {
+        EvaluatorError::UnsupportedSyntax { message, span } => {
+            assert!(message.contains("Backreferences"), "{}", message);
+            assert_eq!((span.start, span.end), (4, 11));
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+    assert!(matches!(
+        evaluator.evaluate("/a(?=b)/.test('ab')").unwrap_err(),
+        EvaluatorError::UnsupportedSyntax { .. }
+    ));
+    assert!(matches!(
+        evaluator.evaluate("/a/y.test('a')").unwrap_err(),
+        EvaluatorError::UnsupportedSyntax { .. }
+    ));
+    assert!(matches!(
+        evaluator.evaluate("/a/.foo()").unwrap_err(),
+        EvaluatorError::UnknownFunction { .. }
+    ));
+}
@@ -0,0 +1,359 @@
+#[test]
+fn test_script_result() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::{json, Value};
+
+    let mut context = HashMap::new();
+    context.insert("price".to_string(), ContextEntry::Variable(json!(120)));
+    let evaluator = Evaluator::new(context);
+    // A script evaluates to its last expression statement.
+    assert_eq!(evaluator.evaluate_script("1; 2").unwrap(), 2);
+    assert_eq!(evaluator.evaluate_script("let a = 1; a + 1").unwrap(), 2);
+    assert_eq!(evaluator.evaluate_script("'a'; 'b'").unwrap(), "b");
+    assert_eq!(
+        evaluator.evaluate_script("let a = 1;").unwrap(),
+        Value::Null
+    );
+    assert_eq!(evaluator.evaluate_script("").unwrap(), Value::Null);
+    assert_eq!(
+        evaluator
+            .evaluate_script("if (price > 100) { 'high' } else { 'low' }")
+            .unwrap(),
+        "high"
+    );
+    assert_eq!(evaluator.evaluate_script("1; if (false) 2;").unwrap(), 1);
+    assert_eq!(
+        evaluator
+            .evaluate_script("let a = 1, b; a = 3; [a, b]")
+            .unwrap(),
+        json!([3, null])
+    );
+}
+
+#[test]
+fn test_script_return() {
+    use std::collections::HashMap;
+
+    use oxide_eval::Evaluator;
+    use serde_json::Value;
+
+    let evaluator = Evaluator::new(HashMap::new());
+    assert_eq!(evaluator.evaluate_script("return 5; 6").unwrap(), 5);
+    assert_eq!(
+        evaluator.evaluate_script("1; return;").unwrap(),
+        Value::Null
+    );
+    assert_eq!(
+        evaluator
+            .evaluate_script("if (true) { return 'early'; } 'late'")
+            .unwrap(),
+        "early"
+    );
+}
+
+#[test]
+fn test_pricing_rule() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert(
+        "cart".to_string(),
+        ContextEntry::Variable(json!([
+            { "sku": "a", "qty": 3, "unit": 10 },
+            { "sku": "b", "qty": 1, "unit": 25 },
+            { "sku": "c", "qty": 0, "unit": 99 },
+        ])),
+    );
+    context.insert(
+        "customer".to_string(),
+        ContextEntry::Variable(json!({ "tier": "gold", "orders": 12 })),
+    );
+    let evaluator = Evaluator::new(context);
+    let script = "
+        const rates = { gold: 20, silver: 10 };
+        let subtotal = 0;
+        for (const item of cart) {
+            if (item.qty > 0) {
+                subtotal += item.qty * item.unit;
+            }
+        }
+        if (subtotal < 50) {
+            return subtotal;
+        }
+        let rate = rates[customer.tier] ?? 0;
+        if (customer.orders > 10) rate += 5;
+        subtotal * (100 - rate) / 100;
+    ";
+    assert_eq!(evaluator.evaluate_script(script).unwrap(), 41.25);
+    let script = script.replace("subtotal < 50", "subtotal < 60");
+    assert_eq!(evaluator.evaluate_script(&script).unwrap(), 55);
+}
+
+#[test]
+fn test_block_scope() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator, EvaluatorError};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert("price".to_string(), ContextEntry::Variable(json!(120)));
+    let evaluator = Evaluator::new(context);
+    assert_eq!(
+        evaluator
+            .evaluate_script("let a = 1; { let a = 2; } a")
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        evaluator
+            .evaluate_script("let a = 1; { a = 2; } a")
+            .unwrap(),
+        2
+    );
+    assert_eq!(
+        evaluator
+            .evaluate_script("let a = 1; { let a = 2; { a += 1; } a }")
+            .unwrap(),
+        3
+    );
+    // Declarations shadow the context, but only in their block.
+    assert_eq!(
+        evaluator.evaluate_script("const price = 5; price").unwrap(),
+        5
+    );
+    assert_eq!(
+        evaluator
+            .evaluate_script("{ const price = 5; } price")
+            .unwrap(),
+        120
+    );
+    assert_eq!(
+        evaluator
+            .evaluate_script("for (const price of [1]) {} price")
+            .unwrap(),
+        120
+    );
+    match evaluator.evaluate_script("{ let b = 1; } b").unwrap_err() {
+        EvaluatorError::VariableNotFound { name, .. } => assert_eq!(name, "b"),
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[test]
+fn test_for_of() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::{json, Value};
+
+    let mut context = HashMap::new();
+    context.insert(
+        "cart".to_string(),
+        ContextEntry::Variable(json!([{ "sku": "a" }, { "sku": "b" }, { "sku": "c" }])),
+    );
+    let evaluator = Evaluator::new(context);
+    assert_eq!(
+        evaluator
+            .evaluate_script("let skus = ''; for (const item of cart) skus += item.sku; skus")
+            .unwrap(),
+        "abc"
+    );
+    // Strings are iterated by code point.
+    assert_eq!(
+        evaluator
+            .evaluate_script("let n = 0; for (const c of 'a😀') n++; n")
+            .unwrap(),
+        2
+    );
+    assert_eq!(
+        evaluator
+            .evaluate_script("let out; for (let x of [1, 2]) { x *= 10; out = x; } out")
+            .unwrap(),
+        20
+    );
+    assert_eq!(
+        evaluator
+            .evaluate_script("for (const x of [1, 2, 3]) { if (x === 2) return x * 100; } 0")
+            .unwrap(),
+        200
+    );
+    assert_eq!(
+        evaluator
+            .evaluate_script("for (const x of []) { 1 }")
+            .unwrap(),
+        Value::Null
+    );
+}
+
+#[test]
+fn test_for_of_iteration() {
+    use std::collections::HashMap;
+
+    use oxide_eval::Evaluator;
+    use serde_json::json;
+
+    let evaluator = Evaluator::new(HashMap::new());
+    // The loop only sees the elements the array had when it started.
+    assert_eq!(
+        evaluator
+            .evaluate_script(
+                "const list = [1, 2]; let n = 0; for (const x of list) { list[list.length] = x; n++; } [n, list]"
+            )
+            .unwrap(),
+        json!([2, [1, 2, 1, 2]])
+    );
+    // Every iteration has its own binding.
+    assert_eq!(
+        evaluator
+            .evaluate_script(
+                "let tags = []; for (const x of [1, 2]) tags = [...tags, s => x * 10]; tags[0]`` + tags[1]``"
+            )
+            .unwrap(),
+        30
+    );
+}
+
+#[test]
+fn test_script_errors() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator, EvaluatorError};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert("price".to_string(), ContextEntry::Variable(json!(120)));
+    let evaluator = Evaluator::new(context);
+    // Constants can't be assigned to, including loop variables.
+    assert!(matches!(
+        evaluator.evaluate_script("const a = 1; a = 2").unwrap_err(),
+        EvaluatorError::TypeError { .. }
+    ));
+    assert!(matches!(
+        evaluator
+            .evaluate_script("const a = 1; { a++; }")
+            .unwrap_err(),
+        EvaluatorError::TypeError { .. }
+    ));
+    assert!(matches!(
+        evaluator
+            .evaluate_script("for (const x of [1]) x = 2;")
+            .unwrap_err(),
+        EvaluatorError::TypeError { .. }
+    ));
+    match evaluator
+        .evaluate_script("let a; for (const x of price) a = x;")
+        .unwrap_err()
+    {
+        EvaluatorError::TypeError { message, span } => {
+            assert_eq!(message, "120 is not iterable");
+            assert_eq!((span.start, span.end), (23, 28));
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+    match evaluator
+        .evaluate_script("let a = 1;\nlet a = 2;")
+        .unwrap_err()
+    {
+        EvaluatorError::Parse { errors, span } => {
+            assert_eq!(
+                errors[0].message,
+                "Identifier 'a' has already been declared"
+            );
+            assert_eq!((span.start, span.end), (15, 16));
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+    assert!(matches!(
+        evaluator.evaluate_script("let a = missing;").unwrap_err(),
+        EvaluatorError::VariableNotFound { .. }
+    ));
+}
+
+#[test]
+fn test_unsupported_statements() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{Evaluator, EvaluatorError};
+
+    let evaluator = Evaluator::new(HashMap::new());
+    // Unsupported statements fail when they are reached.
+    assert!(matches!(
+        evaluator.evaluate_script("var a = 1").unwrap_err(),
+        EvaluatorError::UnsupportedSyntax { .. }
+    ));
+    assert!(matches!(
+        evaluator.evaluate_script("while (true) {}").unwrap_err(),
+        EvaluatorError::UnsupportedSyntax { .. }
+    ));
+    assert!(matches!(
+        evaluator.evaluate_script("1; function f() {}").unwrap_err(),
+        EvaluatorError::UnsupportedSyntax { .. }
+    ));
+    assert_eq!(
+        evaluator
+            .evaluate_script("if (false) { while (true) {} } 1")
+            .unwrap(),
+        1
+    );
+}
+
+#[test]
+fn test_compiled_script() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert(
+        "cart".to_string(),
+        ContextEntry::Variable(json!([{ "qty": 3 }, { "qty": 1 }, { "qty": 0 }])),
+    );
+    let evaluator = Evaluator::new(context);
+    let script =
+        Evaluator::compile_script("let n = 0; for (const x of cart) n += x.qty; n").unwrap();
+    assert_eq!(
+        script.source(),
+        "let n = 0; for (const x of cart) n += x.qty; n"
+    );
+    let res1 = evaluator.evaluate_compiled_script(&script).unwrap();
+    let res2 = evaluator.evaluate_compiled_script(&script).unwrap();
+    assert_eq!(res1, 4);
+    assert_eq!(res2, 4);
+}
+
+#[test]
+fn test_expression_rejects_statements() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator, EvaluatorError};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert("price".to_string(), ContextEntry::Variable(json!(120)));
+    let evaluator = Evaluator::new(context);
+    assert_eq!(evaluator.evaluate("price;").unwrap(), 120);
+    assert_eq!(evaluator.evaluate(";price;;").unwrap(), 120);
+    match evaluator.evaluate("price; 1").unwrap_err() {
+        EvaluatorError::UnsupportedSyntax { span, .. } => assert_eq!(span.start, 7),
+        e => panic!("unexpected error: {:?}", e),
+    }
+    match evaluator.evaluate("'a'; 'b'").unwrap_err() {
+        EvaluatorError::UnsupportedSyntax { span, .. } => assert_eq!(span.start, 5),
+        e => panic!("unexpected error: {:?}", e),
+    }
+    match evaluator.evaluate("'use strict'; 1").unwrap_err() {
+        EvaluatorError::UnsupportedSyntax { span, .. } => assert_eq!(span.start, 14),
+        e => panic!("unexpected error: {:?}", e),
+    }
+    assert!(matches!(
+        evaluator.evaluate("return 1").unwrap_err(),
+        EvaluatorError::Parse { .. }
+    ));
+}
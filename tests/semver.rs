@@ -1,3 +1,5 @@
+#![cfg(feature = "semver-support")]
+
 #[cfg(test)]
 #[test]
 fn test_semver() {
@@ -0,0 +1,110 @@
+use oxide_eval::{context::ContextEntry, Evaluator, EvaluatorError};
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+/// An evaluator with a `log` function that records its first argument and
+/// returns it, plus the shared record.
+fn evaluator() -> (Evaluator, Arc<Mutex<Vec<Value>>>) {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let sink = log.clone();
+    let mut context = HashMap::new();
+    context.insert("score".to_string(), ContextEntry::Variable(json!(5)));
+    context.insert("nothing".to_string(), ContextEntry::Variable(Value::Null));
+    context.insert(
+        "log".to_string(),
+        ContextEntry::Function(Box::new(move |args| {
+            let value = args.first().cloned().unwrap_or_default();
+            sink.lock().unwrap().push(value.clone());
+            value
+        })),
+    );
+    (Evaluator::new(context), log)
+}
+
+#[test]
+fn test_sequence() {
+    let (evaluator, log) = evaluator();
+    assert_eq!(evaluator.evaluate("(log('x'), score > 3)").unwrap(), true);
+    assert_eq!(*log.lock().unwrap(), vec![json!("x")]);
+    assert_eq!(evaluator.evaluate("1, 2, 3").unwrap(), 3);
+    assert_eq!(evaluator.evaluate("[(1, 2), 3]").unwrap(), json!([2, 3]));
+    assert_eq!(evaluator.evaluate("void log('y')").unwrap(), Value::Null);
+}
+
+#[test]
+fn test_sequence_order() {
+    let (evaluator, log) = evaluator();
+    assert_eq!(
+        evaluator
+            .evaluate("(log(1), log(2), [log(3), log(4)], log(5) + log(6))")
+            .unwrap(),
+        11
+    );
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![json!(1), json!(2), json!(3), json!(4), json!(5), json!(6)]
+    );
+}
+
+#[test]
+fn test_sequence_stops_at_error() {
+    let (evaluator, log) = evaluator();
+    match evaluator.evaluate("(log(1), missing, log(2))").unwrap_err() {
+        EvaluatorError::VariableNotFound { name, span } => {
+            assert_eq!(name, "missing");
+            assert_eq!((span.start, span.end), (9, 16));
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+    assert_eq!(*log.lock().unwrap(), vec![json!(1)]);
+}
+
+#[test]
+fn test_call_order() {
+    let (evaluator, log) = evaluator();
+    let cases = [
+        ("(log(1), x => x + 1)(log(2))", json!(3)),
+        ("(log(1), (strings, x) => x)`${log(2)}`", json!(2)),
+        ("[log(1), x => x][1](log(2))", json!(2)),
+    ];
+    for (expression, expected) in cases {
+        log.lock().unwrap().clear();
+        assert_eq!(
+            evaluator.evaluate(expression).unwrap(),
+            expected,
+            "{}",
+            expression
+        );
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![json!(1), json!(2)],
+            "{}",
+            expression
+        );
+    }
+    // A short-circuited optional call doesn't evaluate its arguments.
+    log.lock().unwrap().clear();
+    assert_eq!(
+        evaluator.evaluate("nothing?.foo(log(1))").unwrap(),
+        Value::Null
+    );
+    assert_eq!(
+        evaluator.evaluate("nothing?.[log(2)]").unwrap(),
+        Value::Null
+    );
+    assert!(log.lock().unwrap().is_empty());
+}
+
+#[cfg(feature = "string")]
+#[test]
+fn test_method_call_order() {
+    let (evaluator, log) = evaluator();
+    assert_eq!(
+        evaluator.evaluate("log('a-b').split(log('-'))").unwrap(),
+        json!(["a", "b"])
+    );
+    assert_eq!(*log.lock().unwrap(), vec![json!("a-b"), json!("-")]);
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "string")]
+
 #[cfg(test)]
 #[test]
 fn test_method() {
@@ -96,3 +98,176 @@ fn test_function() {
     assert_eq!(res12, 12);
     assert_eq!(res13, "Hello World!");
 }
+
+#[test]
+fn test_utf16_methods() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::{json, Value};
+
+    let mut context = HashMap::new();
+    context.insert(
+        "s".to_string(),
+        ContextEntry::Variable(json!("héllo 😀 wörld")),
+    );
+    let evaluator = Evaluator::new(context);
+    let cases = [
+        ("s.length", json!(14)),
+        ("s.indexOf('w')", json!(9)),
+        ("s.indexOf('o', 5)", json!(-1)),
+        ("s.lastIndexOf('l')", json!(12)),
+        ("s.lastIndexOf('l', 10)", json!(3)),
+        ("s.substring(6, 8)", json!("😀")),
+        ("s.substring(8, 6)", json!("😀")),
+        ("s.substring(-5, 2)", json!("hé")),
+        ("s.substring(100)", json!("")),
+        ("s.slice(-5)", json!("wörld")),
+        ("s.slice(-5, -3)", json!("wö")),
+        ("s.slice(3, 1)", json!("")),
+        ("s.charAt(1)", json!("é")),
+        ("s.charAt(99)", json!("")),
+        ("s.at(-1)", json!("d")),
+        ("s.at(99)", Value::Null),
+        ("s.charCodeAt(6)", json!(0xd83d)),
+        ("s.charCodeAt(99)", Value::Null),
+        ("s.codePointAt(6)", json!(0x1f600)),
+        ("s.codePointAt(7)", json!(0xde00)),
+        ("s.codePointAt(99)", Value::Null),
+        ("'5'.padStart(3, '0')", json!("005")),
+        ("'abc'.padStart(8, '12')", json!("12121abc")),
+        ("'abc'.padEnd(5)", json!("abc  ")),
+        ("'abc'.padEnd(2, 'x')", json!("abc")),
+        ("'ab'.repeat(3)", json!("ababab")),
+        ("'ab'.repeat(0)", json!("")),
+        ("s.includes('😀')", json!(true)),
+        ("s.includes('h', 1)", json!(false)),
+        ("'  a  '.trimStart()", json!("a  ")),
+        ("'  a  '.trimEnd()", json!("  a")),
+        ("'a-b-c'.replace('-', '+')", json!("a+b-c")),
+        ("'a-b-c'.replaceAll('-', '+')", json!("a+b+c")),
+        ("'ab'.replaceAll('', '.')", json!(".a.b.")),
+        ("'a,b,c'.split(',', 2)", json!(["a", "b"])),
+        ("'a,b,c'.split(',', 0)", json!([])),
+        ("'abc'.split('')", json!(["a", "b", "c"])),
+        ("'abc'.split()", json!(["abc"])),
+        // The same methods through first-argument dispatch.
+        ("padStart('7', 2, '0')", json!("07")),
+        ("slice(s, 0, 5)", json!("héllo")),
+    ];
+    for (expression, expected) in cases {
+        assert_eq!(
+            evaluator.evaluate(expression).unwrap(),
+            expected,
+            "{}",
+            expression
+        );
+    }
+}
+
+#[test]
+fn test_string_methods_never_panic() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert("s".to_string(), ContextEntry::Variable(json!("日本😀")));
+    let evaluator = Evaluator::new(context);
+    for expression in [
+        "s.substring(1, 3)",
+        "s.substring(3, 1)",
+        "s.substring(NaN, Infinity)",
+        "s.slice(3)",
+        "s.slice(-Infinity, 3)",
+        "s.charAt(-1)",
+        "s.at(-Infinity)",
+        "s.split('', 2)",
+        "s.padStart(Infinity)",
+        "s.repeat(-1)",
+        "s.repeat(1e10)",
+        "s.lastIndexOf('', -5)",
+        "s.indexOf('', 100)",
+    ] {
+        let _ = evaluator.evaluate(expression);
+    }
+    assert_eq!(evaluator.evaluate("s.substring(2, 3)").unwrap(), "\u{fffd}");
+}
+
+#[test]
+fn test_replace_first_match_only() {
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    let mut context = HashMap::new();
+    context.insert("path".to_string(), ContextEntry::Variable(json!("a/b/c")));
+    let evaluator = Evaluator::new(context);
+    let cases = [
+        ("path.replace('/', '.')", "a.b/c"),
+        ("replace(path, '/', '.')", "a.b/c"),
+        ("path.replace('x', '.')", "a/b/c"),
+        ("path.replaceAll('/', '.')", "a.b.c"),
+        ("path.replace(/\\//, '.')", "a.b/c"),
+        ("path.replace(/\\//g, '.')", "a.b.c"),
+    ];
+    for (expression, expected) in cases {
+        assert_eq!(
+            evaluator.evaluate(expression).unwrap(),
+            expected,
+            "{}",
+            expression
+        );
+    }
+}
+
+#[test]
+fn test_replace_string_pattern_substitution() {
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    let mut context = HashMap::new();
+    context.insert("s".to_string(), ContextEntry::Variable(json!("abc")));
+    let evaluator = Evaluator::new(context);
+    assert_eq!(
+        evaluator.evaluate("s.replace('b', '$&$&')").unwrap(),
+        "abbc"
+    );
+    assert_eq!(evaluator.evaluate("s.replace('b', '$$')").unwrap(), "a$c");
+    assert_eq!(
+        evaluator.evaluate("s.replace('b', '[$`]')").unwrap(),
+        "a[a]c"
+    );
+    assert_eq!(
+        evaluator.evaluate("s.replace('b', \"[$']\")").unwrap(),
+        "a[c]c"
+    );
+    assert_eq!(evaluator.evaluate("s.replace('x', '$&')").unwrap(), "abc");
+    assert_eq!(
+        evaluator.evaluate("replace(s, 'b', '$&$&')").unwrap(),
+        "abbc"
+    );
+    // There are no capture groups, so these stay as they are.
+    assert_eq!(
+        evaluator.evaluate("s.replace('b', '$1$<n>$')").unwrap(),
+        "a$1$<n>$c"
+    );
+    assert_eq!(
+        evaluator
+            .evaluate("'a.b.c'.replaceAll('.', '($&)')")
+            .unwrap(),
+        "a(.)b(.)c"
+    );
+    assert_eq!(
+        evaluator
+            .evaluate("'a.b.c'.replaceAll('.', \"$'\")")
+            .unwrap(),
+        "ab.cbcc"
+    );
+    assert_eq!(
+        evaluator.evaluate("'ab'.replaceAll('', '[$`]')").unwrap(),
+        "[]a[a]b[ab]"
+    );
+}
@@ -0,0 +1,139 @@
+use oxide_eval::{
+    context::{ContextEntry, HostError, TemplateStrings},
+    Evaluator, EvaluatorError,
+};
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+/// Builds a parameterized query: values never become part of the SQL text.
+fn sql(strings: TemplateStrings, values: Vec<Value>) -> Result<Value, HostError> {
+    let mut text = String::new();
+    for (index, part) in strings.cooked.iter().enumerate() {
+        text.push_str(part.as_deref().ok_or("invalid escape")?);
+        if index < values.len() {
+            text.push_str(&format!("${}", index + 1));
+        }
+    }
+    Ok(json!({ "text": text, "params": values }))
+}
+
+fn html(strings: TemplateStrings, values: Vec<Value>) -> Result<Value, HostError> {
+    let mut result = strings.cooked[0].clone().unwrap_or_default();
+    for (value, part) in values.iter().zip(&strings.cooked[1..]) {
+        let value = match value {
+            Value::String(s) => s.clone(),
+            value => value.to_string(),
+        };
+        result.push_str(&value.replace('&', "&amp;").replace('<', "&lt;"));
+        result.push_str(part.as_deref().unwrap_or_default());
+    }
+    Ok(Value::String(result))
+}
+
+fn evaluator() -> Evaluator {
+    let mut context = HashMap::new();
+    context.insert(
+        "user".to_string(),
+        ContextEntry::Variable(json!({ "id": 7, "name": "<b>Ada</b>" })),
+    );
+    context.insert("sql".to_string(), ContextEntry::TagFunction(Box::new(sql)));
+    context.insert(
+        "html".to_string(),
+        ContextEntry::TagFunction(Box::new(html)),
+    );
+    context.insert(
+        "args".to_string(),
+        ContextEntry::Function(Box::new(Value::Array)),
+    );
+    Evaluator::new(context)
+}
+
+#[test]
+fn test_tag_function() {
+    let evaluator = evaluator();
+    assert_eq!(
+        evaluator
+            .evaluate("sql`SELECT * FROM users WHERE id = ${user.id} AND name = ${user.name}`")
+            .unwrap(),
+        json!({
+            "text": "SELECT * FROM users WHERE id = $1 AND name = $2",
+            "params": [7, "<b>Ada</b>"],
+        })
+    );
+    assert_eq!(
+        evaluator.evaluate("html`<p>${user.name}</p>`").unwrap(),
+        "<p>&lt;b>Ada&lt;/b></p>"
+    );
+    assert_eq!(evaluator.evaluate("html`${user.id}`.length").unwrap(), 1);
+    assert_eq!(
+        evaluator
+            .evaluate("html`<p>${`${user.id} & ${user.name}`}</p>`")
+            .unwrap(),
+        "<p>7 &amp; &lt;b>Ada&lt;/b></p>"
+    );
+}
+
+#[test]
+fn test_tag_function_strings() {
+    let received = Arc::new(Mutex::new(None));
+    let sink = received.clone();
+    let mut context = HashMap::new();
+    context.insert(
+        "tag".to_string(),
+        ContextEntry::TagFunction(Box::new(move |strings, values| {
+            *sink.lock().unwrap() = Some((strings, values));
+            Ok(Value::Null)
+        })),
+    );
+    let evaluator = Evaluator::new(context);
+    evaluator.evaluate("tag`a\\n${1}\\unicode${'x'}`").unwrap();
+    let (strings, values) = received.lock().unwrap().take().unwrap();
+    assert_eq!(
+        strings,
+        TemplateStrings {
+            cooked: vec![Some("a\n".to_string()), None, Some("".to_string())],
+            raw: vec!["a\\n".to_string(), "\\unicode".to_string(), "".to_string()],
+        }
+    );
+    assert_eq!(values, vec![json!(1), json!("x")]);
+}
+
+#[test]
+fn test_other_functions_as_tags() {
+    let evaluator = evaluator();
+    // Plain host functions get the cooked strings followed by the values.
+    assert_eq!(
+        evaluator.evaluate("args`a${1}b${2}`").unwrap(),
+        json!([["a", "b", ""], 1, 2])
+    );
+    assert_eq!(
+        evaluator
+            .evaluate("((strings, value) => strings.raw[0] + value + strings[1])`a\\n${1}b`")
+            .unwrap(),
+        "a\\n1b"
+    );
+}
+
+#[test]
+fn test_tag_errors() {
+    let evaluator = evaluator();
+    match evaluator.evaluate("sql`${1}\\unicode`").unwrap_err() {
+        EvaluatorError::HostFunction { name, source, span } => {
+            assert_eq!(name, "sql");
+            assert_eq!(source.to_string(), "invalid escape");
+            assert_eq!((span.start, span.end), (0, 17));
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+    assert!(matches!(
+        evaluator.evaluate("missing`x`").unwrap_err(),
+        EvaluatorError::UnknownFunction { .. }
+    ));
+    assert!(matches!(
+        evaluator.evaluate("user`x`").unwrap_err(),
+        EvaluatorError::TypeError { .. }
+    ));
+}
@@ -0,0 +1,129 @@
+#[test]
+fn test_template_literal() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert(
+        "user".to_string(),
+        ContextEntry::Variable(json!({ "name": "Ada", "unread": 3 })),
+    );
+    let evaluator = Evaluator::new(context);
+    assert_eq!(evaluator.evaluate("`plain`").unwrap(), "plain");
+    assert_eq!(evaluator.evaluate("``").unwrap(), "");
+    assert_eq!(evaluator.evaluate("`Hi ${user.name}!`").unwrap(), "Hi Ada!");
+    assert_eq!(
+        evaluator
+            .evaluate("`${user.name} has ${user.unread} new message${user.unread == 1 ? '' : 's'}`")
+            .unwrap(),
+        "Ada has 3 new messages"
+    );
+    assert_eq!(evaluator.evaluate("`${user.unread}`").unwrap(), "3");
+    assert_eq!(evaluator.evaluate("`a` + `b`").unwrap(), "ab");
+}
+
+#[test]
+fn test_template_literal_conversion() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert(
+        "tags".to_string(),
+        ContextEntry::Variable(json!(["a", "b"])),
+    );
+    let evaluator = Evaluator::new(context);
+    assert_eq!(
+        evaluator
+            .evaluate("`${1 + 1}${'x'}${null}-${undefined}`")
+            .unwrap(),
+        "2xnull-undefined"
+    );
+    assert_eq!(evaluator.evaluate("`${tags}`").unwrap(), "a,b");
+    assert_eq!(evaluator.evaluate("`${{}}`").unwrap(), "[object Object]");
+    assert_eq!(
+        evaluator.evaluate("`${0.1 + 0.2}`").unwrap(),
+        "0.30000000000000004"
+    );
+    assert_eq!(evaluator.evaluate("`${-0}`").unwrap(), "0");
+    assert_eq!(evaluator.evaluate("`${1 / 0}`").unwrap(), "Infinity");
+    assert_eq!(evaluator.evaluate("`${true}`").unwrap(), "true");
+}
+
+#[test]
+fn test_template_literal_escapes_and_newlines() {
+    use std::collections::HashMap;
+
+    use oxide_eval::Evaluator;
+
+    let evaluator = Evaluator::new(HashMap::new());
+    assert_eq!(
+        evaluator
+            .evaluate("`tab:\\t|dollar:\\${x}|brace:{}`")
+            .unwrap(),
+        "tab:\t|dollar:${x}|brace:{}"
+    );
+    assert_eq!(
+        evaluator.evaluate("`line 1\nline ${2}\n`").unwrap(),
+        "line 1\nline 2\n"
+    );
+    assert_eq!(evaluator.evaluate("`$ and {}`").unwrap(), "$ and {}");
+}
+
+#[test]
+fn test_nested_template_literal() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert("name".to_string(), ContextEntry::Variable(json!("Ada")));
+    let evaluator = Evaluator::new(context);
+    assert_eq!(
+        evaluator
+            .evaluate("`outer ${`inner ${name.length}`} end`")
+            .unwrap(),
+        "outer inner 3 end"
+    );
+    assert_eq!(evaluator.evaluate("`${`${`${1}`}`}`").unwrap(), "1");
+}
+
+#[test]
+fn test_template_literal_errors() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator, EvaluatorError};
+    use serde_json::{json, Value};
+
+    let mut context = HashMap::new();
+    context.insert(
+        "tags".to_string(),
+        ContextEntry::Variable(json!(["a", "b"])),
+    );
+    context.insert("nothing".to_string(), ContextEntry::Variable(Value::Null));
+    let evaluator = Evaluator::new(context);
+    match evaluator.evaluate("`Hi ${missing}`").unwrap_err() {
+        EvaluatorError::VariableNotFound { name, span } => {
+            assert_eq!(name, "missing");
+            assert_eq!((span.start, span.end), (6, 13));
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+    assert!(matches!(
+        evaluator.evaluate("`${tags[5]}`").unwrap_err(),
+        EvaluatorError::IndexOutOfRange { index: 5, .. }
+    ));
+    assert!(matches!(
+        evaluator.evaluate("`${nothing.name}`").unwrap_err(),
+        EvaluatorError::TypeError { .. }
+    ));
+    assert!(matches!(
+        evaluator.evaluate("`unterminated ${1}").unwrap_err(),
+        EvaluatorError::Parse { .. }
+    ));
+}
@@ -0,0 +1,309 @@
+#[test]
+fn test_typeof() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::{json, Value};
+
+    let mut context = HashMap::new();
+    context.insert("nothing".to_string(), ContextEntry::Variable(Value::Null));
+    context.insert("flag".to_string(), ContextEntry::Variable(json!(true)));
+    context.insert("count".to_string(), ContextEntry::Variable(json!(3)));
+    context.insert("name".to_string(), ContextEntry::Variable(json!("Ada")));
+    context.insert("list".to_string(), ContextEntry::Variable(json!([10, 20])));
+    context.insert(
+        "record".to_string(),
+        ContextEntry::Variable(json!({ "id": 1 })),
+    );
+    let evaluator = Evaluator::new(context);
+    assert_eq!(evaluator.evaluate("typeof nothing").unwrap(), "object");
+    assert_eq!(evaluator.evaluate("typeof flag").unwrap(), "boolean");
+    assert_eq!(evaluator.evaluate("typeof count").unwrap(), "number");
+    assert_eq!(evaluator.evaluate("typeof name").unwrap(), "string");
+    assert_eq!(evaluator.evaluate("typeof list").unwrap(), "object");
+    assert_eq!(evaluator.evaluate("typeof record").unwrap(), "object");
+    assert_eq!(evaluator.evaluate("typeof record.id").unwrap(), "number");
+    assert_eq!(evaluator.evaluate("typeof undefined").unwrap(), "undefined");
+    assert_eq!(evaluator.evaluate("typeof NaN").unwrap(), "number");
+    assert_eq!(evaluator.evaluate("typeof /a/").unwrap(), "object");
+    assert_eq!(evaluator.evaluate("typeof typeof 1").unwrap(), "string");
+    assert_eq!(evaluator.evaluate("typeof `${count}`").unwrap(), "string");
+    assert_eq!(
+        evaluator.evaluate("typeof name === 'string'").unwrap(),
+        true
+    );
+}
+
+#[test]
+fn test_typeof_functions() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert(
+        "now".to_string(),
+        ContextEntry::Function(Box::new(|_| json!(0))),
+    );
+    let evaluator = Evaluator::new(context);
+    assert_eq!(evaluator.evaluate("typeof (x => x)").unwrap(), "function");
+    assert_eq!(
+        evaluator.evaluate("typeof function () {}").unwrap(),
+        "function"
+    );
+    assert_eq!(evaluator.evaluate("typeof now").unwrap(), "function");
+    assert_eq!(evaluator.evaluate("typeof now()").unwrap(), "number");
+}
+
+#[test]
+fn test_typeof_undeclared_variable() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator, EvaluatorError};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert(
+        "record".to_string(),
+        ContextEntry::Variable(json!({ "id": 1 })),
+    );
+    let evaluator = Evaluator::new(context);
+    assert_eq!(
+        evaluator.evaluate("typeof missingVar").unwrap(),
+        "undefined"
+    );
+    assert_eq!(
+        evaluator.evaluate("typeof record?.missing").unwrap(),
+        "undefined"
+    );
+    // Only a bare identifier may be undeclared.
+    match evaluator.evaluate("typeof missingVar.field").unwrap_err() {
+        EvaluatorError::VariableNotFound { name, .. } => assert_eq!(name, "missingVar"),
+        e => panic!("unexpected error: {:?}", e),
+    }
+    assert!(matches!(
+        evaluator.evaluate("typeof (missingVar + 1)").unwrap_err(),
+        EvaluatorError::VariableNotFound { .. }
+    ));
+}
+
+#[test]
+fn test_typeof_missing_member() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator, EvaluatorError};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert(
+        "record".to_string(),
+        ContextEntry::Variable(json!({ "a": { "b": 1 }, "empty": null })),
+    );
+    context.insert("list".to_string(), ContextEntry::Variable(json!([1, 2])));
+    let evaluator = Evaluator::new(context);
+    assert_eq!(
+        evaluator.evaluate("typeof record.missing").unwrap(),
+        "undefined"
+    );
+    assert_eq!(
+        evaluator.evaluate("typeof record.a.c").unwrap(),
+        "undefined"
+    );
+    assert_eq!(
+        evaluator.evaluate("typeof record['a']['c']").unwrap(),
+        "undefined"
+    );
+    assert_eq!(evaluator.evaluate("typeof record.a.b").unwrap(), "number");
+    assert_eq!(evaluator.evaluate("typeof list[10]").unwrap(), "undefined");
+    assert_eq!(evaluator.evaluate("typeof list[1]").unwrap(), "number");
+    assert_eq!(
+        evaluator.evaluate("typeof record.empty?.x").unwrap(),
+        "undefined"
+    );
+    // Reading from a missing property is still an error, as in JavaScript.
+    assert!(matches!(
+        evaluator.evaluate("typeof record.missing.x").unwrap_err(),
+        EvaluatorError::TypeError { .. }
+    ));
+    assert!(matches!(
+        evaluator.evaluate("typeof list[10].x").unwrap_err(),
+        EvaluatorError::TypeError { .. }
+    ));
+}
+
+#[test]
+fn test_void() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator, EvaluatorError};
+    use serde_json::{json, Value};
+
+    let mut context = HashMap::new();
+    context.insert("count".to_string(), ContextEntry::Variable(json!(3)));
+    let evaluator = Evaluator::new(context);
+    assert_eq!(evaluator.evaluate("void 0").unwrap(), Value::Null);
+    assert_eq!(
+        evaluator.evaluate("void count === undefined").unwrap(),
+        true
+    );
+    assert!(matches!(
+        evaluator.evaluate("void missingVar").unwrap_err(),
+        EvaluatorError::VariableNotFound { .. }
+    ));
+}
+
+#[test]
+fn test_in() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert("list".to_string(), ContextEntry::Variable(json!([10, 20])));
+    context.insert(
+        "record".to_string(),
+        ContextEntry::Variable(json!({ "id": 1, "tags": null })),
+    );
+    let evaluator = Evaluator::new(context);
+    assert_eq!(evaluator.evaluate("'id' in record").unwrap(), true);
+    // A property that is `null` still exists.
+    assert_eq!(evaluator.evaluate("'tags' in record").unwrap(), true);
+    assert_eq!(evaluator.evaluate("'missing' in record").unwrap(), false);
+    assert_eq!(evaluator.evaluate("0 in list").unwrap(), true);
+    assert_eq!(evaluator.evaluate("'1' in list").unwrap(), true);
+    assert_eq!(evaluator.evaluate("2 in list").unwrap(), false);
+    assert_eq!(evaluator.evaluate("-1 in list").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'01' in list").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'length' in list").unwrap(), true);
+    assert_eq!(evaluator.evaluate("'source' in /a/").unwrap(), true);
+    assert_eq!(evaluator.evaluate("'key' in {}").unwrap(), false);
+    assert_eq!(evaluator.evaluate("'x' in (x => x)").unwrap(), false);
+}
+
+#[test]
+fn test_in_errors() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator, EvaluatorError};
+    use serde_json::{json, Value};
+
+    let mut context = HashMap::new();
+    context.insert("nothing".to_string(), ContextEntry::Variable(Value::Null));
+    context.insert("count".to_string(), ContextEntry::Variable(json!(3)));
+    context.insert("name".to_string(), ContextEntry::Variable(json!("Ada")));
+    let evaluator = Evaluator::new(context);
+    // The right-hand side must be an object.
+    assert!(matches!(
+        evaluator.evaluate("'a' in name").unwrap_err(),
+        EvaluatorError::TypeError { .. }
+    ));
+    assert!(matches!(
+        evaluator.evaluate("'a' in count").unwrap_err(),
+        EvaluatorError::TypeError { .. }
+    ));
+    assert!(matches!(
+        evaluator.evaluate("'a' in nothing").unwrap_err(),
+        EvaluatorError::TypeError { .. }
+    ));
+    assert!(matches!(
+        evaluator.evaluate("'a' in undefined").unwrap_err(),
+        EvaluatorError::TypeError { .. }
+    ));
+    assert!(matches!(
+        evaluator.evaluate("'a' in missingVar").unwrap_err(),
+        EvaluatorError::VariableNotFound { .. }
+    ));
+}
+
+#[test]
+fn test_instanceof() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::{json, Value};
+
+    let mut context = HashMap::new();
+    context.insert("nothing".to_string(), ContextEntry::Variable(Value::Null));
+    context.insert("list".to_string(), ContextEntry::Variable(json!([10, 20])));
+    context.insert("name".to_string(), ContextEntry::Variable(json!("Ada")));
+    context.insert(
+        "record".to_string(),
+        ContextEntry::Variable(json!({ "id": 1 })),
+    );
+    let evaluator = Evaluator::new(context);
+    assert_eq!(evaluator.evaluate("list instanceof Array").unwrap(), true);
+    assert_eq!(
+        evaluator.evaluate("record instanceof Array").unwrap(),
+        false
+    );
+    assert_eq!(
+        evaluator.evaluate("record instanceof Object").unwrap(),
+        true
+    );
+    assert_eq!(evaluator.evaluate("list instanceof Object").unwrap(), true);
+    assert_eq!(evaluator.evaluate("name instanceof Object").unwrap(), false);
+    assert_eq!(
+        evaluator.evaluate("nothing instanceof Object").unwrap(),
+        false
+    );
+    assert_eq!(evaluator.evaluate("/a/ instanceof RegExp").unwrap(), true);
+    assert_eq!(
+        evaluator.evaluate("(x => x) instanceof Function").unwrap(),
+        true
+    );
+    assert_eq!(
+        evaluator.evaluate("list instanceof (x => x)").unwrap(),
+        false
+    );
+}
+
+#[test]
+fn test_instanceof_wrapper_constructors() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert("count".to_string(), ContextEntry::Variable(json!(3)));
+    context.insert("name".to_string(), ContextEntry::Variable(json!("Ada")));
+    context.insert(
+        "record".to_string(),
+        ContextEntry::Variable(json!({ "id": 1 })),
+    );
+    let evaluator = Evaluator::new(context);
+    // Primitives aren't instances of their wrappers.
+    assert_eq!(evaluator.evaluate("name instanceof String").unwrap(), false);
+    assert_eq!(
+        evaluator.evaluate("count instanceof Number").unwrap(),
+        false
+    );
+    assert_eq!(
+        evaluator.evaluate("true instanceof Boolean").unwrap(),
+        false
+    );
+    assert_eq!(evaluator.evaluate("record instanceof Date").unwrap(), false);
+}
+
+#[test]
+fn test_instanceof_errors() {
+    use std::collections::HashMap;
+
+    use oxide_eval::{context::ContextEntry, Evaluator, EvaluatorError};
+    use serde_json::json;
+
+    let mut context = HashMap::new();
+    context.insert("count".to_string(), ContextEntry::Variable(json!(3)));
+    context.insert("list".to_string(), ContextEntry::Variable(json!([10, 20])));
+    let evaluator = Evaluator::new(context);
+    assert!(matches!(
+        evaluator.evaluate("list instanceof count").unwrap_err(),
+        EvaluatorError::TypeError { .. }
+    ));
+    match evaluator.evaluate("list instanceof Widget").unwrap_err() {
+        EvaluatorError::VariableNotFound { name, .. } => assert_eq!(name, "Widget"),
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
@@ -0,0 +1,117 @@
+use oxide_eval::{
+    context::ContextEntry, Evaluator, EvaluatorError, NonFiniteOutput, OutputPolicy,
+    UndefinedOutput,
+};
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+fn evaluator() -> Evaluator {
+    let mut context = HashMap::new();
+    context.insert("zero".to_string(), ContextEntry::Variable(json!(0)));
+    context.insert("user".to_string(), ContextEntry::Variable(json!({})));
+    Evaluator::new(context)
+}
+
+#[test]
+fn test_non_finite_numbers_default_to_null() {
+    let evaluator = evaluator();
+    assert_eq!(evaluator.evaluate("1 / 0").unwrap(), Value::Null);
+    assert_eq!(evaluator.evaluate("0 / 0").unwrap(), Value::Null);
+    assert_eq!(evaluator.evaluate("+'abc'").unwrap(), Value::Null);
+    assert_eq!(evaluator.evaluate("undefined").unwrap(), Value::Null);
+}
+
+#[test]
+fn test_special_values_survive_intermediate_steps() {
+    let evaluator = evaluator();
+    assert_eq!(evaluator.evaluate("1 / 0 > 1e308").unwrap(), true);
+    assert_eq!(evaluator.evaluate("1 / -0 < 0").unwrap(), true);
+    assert_eq!(evaluator.evaluate("1 / -zero < 0").unwrap(), true);
+    assert_eq!(evaluator.evaluate("-0 === 0").unwrap(), true);
+    assert_eq!(evaluator.evaluate("NaN === NaN").unwrap(), false);
+    assert_eq!(evaluator.evaluate("!NaN").unwrap(), true);
+    assert_eq!(
+        evaluator.evaluate("Infinity - Infinity || 'nan'").unwrap(),
+        "nan"
+    );
+    assert_eq!(evaluator.evaluate("'' + 0 / 0").unwrap(), "NaN");
+    assert_eq!(evaluator.evaluate("'' + -1 / 0").unwrap(), "-Infinity");
+    assert_eq!(evaluator.evaluate("'' + undefined").unwrap(), "undefined");
+    assert_eq!(evaluator.evaluate("'' + 1e21").unwrap(), "1e+21");
+    assert_eq!(
+        evaluator.evaluate("undefined ?? 'fallback'").unwrap(),
+        "fallback"
+    );
+    assert_eq!(
+        evaluator.evaluate("user?.name ?? 'anonymous'").unwrap(),
+        "anonymous"
+    );
+}
+
+#[test]
+fn test_number_results() {
+    let evaluator = evaluator();
+    assert_eq!(evaluator.evaluate("-0").unwrap(), json!(0));
+    assert_eq!(evaluator.evaluate("0 % 5").unwrap(), json!(0));
+    assert_eq!(evaluator.evaluate("-5 % 3").unwrap(), json!(-2));
+    assert_eq!(evaluator.evaluate("0.1 + 0.2").unwrap(), json!(0.1 + 0.2));
+    assert_eq!(
+        evaluator.evaluate("2 ** 53").unwrap(),
+        json!(9007199254740992.0)
+    );
+    assert_eq!(evaluator.evaluate("1 ** Infinity").unwrap(), Value::Null);
+}
+
+#[test]
+fn test_non_finite_as_string() {
+    let evaluator = evaluator().with_output_policy(OutputPolicy {
+        non_finite: NonFiniteOutput::String,
+        ..OutputPolicy::default()
+    });
+    assert_eq!(evaluator.evaluate("1 / 0").unwrap(), "Infinity");
+    assert_eq!(evaluator.evaluate("-1 / 0").unwrap(), "-Infinity");
+    assert_eq!(evaluator.evaluate("[0 / 0]").unwrap(), json!(["NaN"]));
+    assert_eq!(evaluator.evaluate("undefined").unwrap(), Value::Null);
+}
+
+#[test]
+fn test_strict_policy() {
+    let evaluator = evaluator().with_output_policy(OutputPolicy::STRICT);
+    for expression in ["undefined", "0 / 0", "[1, 1 / 0]", "user?.name"] {
+        assert!(
+            matches!(
+                evaluator.evaluate(expression),
+                Err(EvaluatorError::TypeCoercion { .. })
+            ),
+            "{}",
+            expression
+        );
+    }
+    assert_eq!(evaluator.evaluate("1 / 0 > 0").unwrap(), true);
+}
+
+#[test]
+fn test_policy_applies_to_host_arguments() {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink = received.clone();
+    let mut context = HashMap::new();
+    context.insert(
+        "record".to_string(),
+        ContextEntry::Function(Box::new(move |args| {
+            sink.lock().unwrap().extend(args);
+            Value::Null
+        })),
+    );
+    let evaluator = Evaluator::new(context).with_output_policy(OutputPolicy {
+        undefined: UndefinedOutput::Null,
+        non_finite: NonFiniteOutput::String,
+    });
+    evaluator.evaluate("record(undefined, 1 / 0, 2)").unwrap();
+    assert_eq!(
+        *received.lock().unwrap(),
+        vec![Value::Null, json!("Infinity"), json!(2)]
+    );
+}